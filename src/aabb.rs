@@ -0,0 +1,76 @@
+use nalgebra_glm::Vec3;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Aabb {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Aabb {
+    pub fn new(min: Vec3, max: Vec3) -> Self {
+        Aabb { min, max }
+    }
+
+    pub fn empty() -> Self {
+        Aabb {
+            min: Vec3::repeat(f32::INFINITY),
+            max: Vec3::repeat(f32::NEG_INFINITY),
+        }
+    }
+
+    pub fn infinite() -> Self {
+        Aabb {
+            min: Vec3::repeat(f32::NEG_INFINITY),
+            max: Vec3::repeat(f32::INFINITY),
+        }
+    }
+
+    pub fn is_finite(&self) -> bool {
+        self.min.iter().chain(self.max.iter()).all(|v| v.is_finite())
+    }
+
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        Aabb {
+            min: self.min.inf(&other.min),
+            max: self.max.sup(&other.max),
+        }
+    }
+
+    pub fn center(&self) -> Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    pub fn surface_area(&self) -> f32 {
+        let d = self.max - self.min;
+        if d.x < 0.0 || d.y < 0.0 || d.z < 0.0 {
+            return 0.0;
+        }
+        2.0 * (d.x * d.y + d.y * d.z + d.z * d.x)
+    }
+
+    // Prueba de placas; devuelve la distancia de entrada si el rayo toca la caja
+    // antes de `max_distance`. Las cajas planas (grosor 0) también funcionan.
+    pub fn ray_entry(&self, ray_origin: &Vec3, inv_direction: &Vec3, max_distance: f32) -> Option<f32> {
+        let mut t_min = 0.0f32;
+        let mut t_max = max_distance;
+
+        for axis in 0..3 {
+            let t0 = (self.min[axis] - ray_origin[axis]) * inv_direction[axis];
+            let t1 = (self.max[axis] - ray_origin[axis]) * inv_direction[axis];
+            let (near, far) = if t0 < t1 { (t0, t1) } else { (t1, t0) };
+
+            // NaN aparece cuando el rayo es paralelo y está justo sobre una cara
+            if !near.is_nan() {
+                t_min = t_min.max(near);
+            }
+            if !far.is_nan() {
+                t_max = t_max.min(far);
+            }
+            if t_min > t_max {
+                return None;
+            }
+        }
+
+        Some(t_min)
+    }
+}
@@ -0,0 +1,55 @@
+use nalgebra_glm::Vec3;
+
+use crate::color::Color;
+use crate::integrator::{closest_intersect, cosine_hemisphere, offset_origin, Integrator};
+use crate::ray_differential::RayDifferential;
+use crate::sampler::Sampler;
+use crate::scene::Scene;
+
+pub const DEFAULT_AO_SAMPLES: usize = 16;
+pub const DEFAULT_AO_RADIUS: f32 = 1.0;
+
+// Oclusión ambiental: fracción del hemisferio sobre cada punto que no está
+// tapada por otra geometría a menos de `radius`. Ignora luces y materiales.
+pub struct AmbientOcclusion {
+    pub samples: usize,
+    pub radius: f32,
+}
+
+impl AmbientOcclusion {
+    pub fn new(samples: usize, radius: f32) -> Self {
+        AmbientOcclusion { samples, radius }
+    }
+}
+
+impl Integrator for AmbientOcclusion {
+    fn radiance(
+        &self,
+        scene: &Scene,
+        ray_origin: &Vec3,
+        ray_direction: &Vec3,
+        _differential: &RayDifferential,
+        sampler: &mut Sampler,
+    ) -> Color {
+        let intersect = closest_intersect(ray_origin, ray_direction, &scene.objects);
+        if !intersect.is_intersecting {
+            return Color::new(1.0, 1.0, 1.0);
+        }
+
+        let normal = if intersect.normal.dot(ray_direction) > 0.0 { -intersect.normal } else { intersect.normal };
+        let samples = self.samples.max(1);
+        let mut visible = 0;
+
+        for _ in 0..samples {
+            let direction = cosine_hemisphere(&normal, sampler);
+            let origin = offset_origin(&intersect, &direction);
+            let hit = closest_intersect(&origin, &direction, &scene.objects);
+            if !hit.is_intersecting || hit.distance > self.radius {
+                visible += 1;
+            }
+        }
+
+        let occlusion = visible as f32 / samples as f32;
+        Color::new(occlusion, occlusion, occlusion)
+    }
+}
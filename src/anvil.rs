@@ -0,0 +1,414 @@
+use nalgebra_glm::Vec3;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+use crate::aabb::Aabb;
+use crate::bvh::Bvh;
+use crate::import::ImportError;
+use crate::nbt::{self, Tag};
+use crate::ray_intersect::{Intersect, RayIntersect};
+use crate::schematic::{block_state, BlockTable};
+use crate::voxel_grid::{VoxelGrid, DEFAULT_BLOCK_SIZE};
+
+// Chunks cargados alrededor de la cámara en cada dirección
+pub const DEFAULT_VIEW_DISTANCE: u32 = 4;
+// Desde la 20w17a (1.16) los valores empaquetados no cruzan de un long a otro
+const NON_SPANNING_DATA_VERSION: i64 = 2527;
+const SECTOR_SIZE: u64 = 4096;
+// Altura máxima de la caja, la de un mundo con el límite de construcción más alto
+const MAX_HEIGHT: i32 = 4064;
+
+// Parte de un mundo que se dibuja. `min` y `max` son coordenadas de bloque
+// de Minecraft (incluidas) y `origin` es la posición en la escena de la
+// esquina de `min`.
+pub struct WorldOptions {
+    pub min: [i32; 3],
+    pub max: [i32; 3],
+    pub origin: Vec3,
+    pub block_size: f32,
+    pub view_distance: u32,
+    pub blocks: BlockTable,
+}
+
+impl WorldOptions {
+    pub fn new(min: [i32; 3], max: [i32; 3]) -> Self {
+        WorldOptions {
+            min,
+            max,
+            origin: Vec3::zeros(),
+            block_size: DEFAULT_BLOCK_SIZE,
+            view_distance: DEFAULT_VIEW_DISTANCE,
+            blocks: BlockTable::new(),
+        }
+    }
+}
+
+struct LoadedChunks {
+    // None marca un chunk ya leído que no tiene bloques visibles
+    grids: HashMap<(i32, i32), Option<Arc<VoxelGrid>>>,
+    bvh: Bvh,
+}
+
+// Mundo de Minecraft guardado en formato Anvil (region/r.X.Z.mca). Los chunks
+// se leen bajo demanda con `load_around`, cada uno como una rejilla de bloques,
+// y los que quedan lejos de la cámara se descartan.
+pub struct AnvilWorld {
+    pub region_dir: PathBuf,
+    pub options: WorldOptions,
+    chunks: RwLock<LoadedChunks>,
+}
+
+fn chunk_of(block: i32) -> i32 {
+    block.div_euclid(16)
+}
+
+// Valor `index` de un arreglo de longs empaquetados a `bits` bits
+fn unpack(data: &[i64], bits: usize, index: usize, spanning: bool) -> Option<usize> {
+    let mask = (1u64 << bits) - 1;
+    if spanning {
+        let bit = index * bits;
+        let (word, offset) = (bit / 64, bit % 64);
+        let mut value = (*data.get(word)? as u64) >> offset;
+        if offset + bits > 64 {
+            value |= (*data.get(word + 1)? as u64) << (64 - offset);
+        }
+        Some((value & mask) as usize)
+    } else {
+        let per_long = 64 / bits;
+        let word = *data.get(index / per_long)? as u64;
+        Some(((word >> ((index % per_long) * bits)) & mask) as usize)
+    }
+}
+
+// Alturas de la sección `section_y` que caen dentro de [min_y, max_y]. Un `Y`
+// corrupto que no cabe en i32 deja la sección fuera de la caja.
+fn section_rows(section_y: i64, min_y: i32, max_y: i32) -> Option<(i32, i32)> {
+    let bottom = i32::try_from(section_y).ok()?.checked_mul(16)?;
+    let low = bottom.max(min_y);
+    let high = bottom.saturating_add(15).min(max_y);
+    (low <= high).then_some((low, high))
+}
+
+// Posición de un bloque en los datos de su sección, que van en orden y, z, x
+fn section_index(x: i32, y: i32, z: i32) -> usize {
+    ((y.rem_euclid(16) * 16 + z.rem_euclid(16)) * 16 + x.rem_euclid(16)) as usize
+}
+
+impl AnvilWorld {
+    pub fn open(world_dir: &Path, options: WorldOptions) -> Result<Self, ImportError> {
+        let region_dir = world_dir.join("region");
+        if !region_dir.is_dir() {
+            return Err(ImportError::new(world_dir, "not a world save, 'region' directory not found"));
+        }
+        for axis in 0..3 {
+            // El tamaño de la caja, max - min + 1, tiene que caber en un i32
+            let Some(extent) = options.max[axis].checked_sub(options.min[axis]).and_then(|e| e.checked_add(1)) else {
+                return Err(ImportError::new(world_dir, "world bounds are too large"));
+            };
+            if extent <= 0 {
+                return Err(ImportError::new(world_dir, "world bounds have min greater than max"));
+            }
+        }
+        if options.max[1] - options.min[1] >= MAX_HEIGHT {
+            return Err(ImportError::new(world_dir, format!("world bounds are taller than {} blocks", MAX_HEIGHT)));
+        }
+
+        Ok(AnvilWorld {
+            region_dir,
+            options,
+            chunks: RwLock::new(LoadedChunks {
+                grids: HashMap::new(),
+                bvh: Bvh::new(Vec::new()),
+            }),
+        })
+    }
+
+    // Carga los chunks de la caja que están a `view_distance` chunks o menos
+    // de `position` (en coordenadas de la escena) y descarta los demás.
+    // Devuelve si cambió algo. Un chunk ilegible se deja vacío y se informa.
+    pub fn load_around(&self, position: &Vec3) -> Result<bool, ImportError> {
+        let options = &self.options;
+        let block = (position - options.origin) / options.block_size;
+        let center = (
+            chunk_of(options.min[0].saturating_add(block.x.floor() as i32)),
+            chunk_of(options.min[2].saturating_add(block.z.floor() as i32)),
+        );
+        let distance = options.view_distance.min(i32::MAX as u32) as i32;
+        let x_range = center.0.saturating_sub(distance).max(chunk_of(options.min[0]))
+            ..=center.0.saturating_add(distance).min(chunk_of(options.max[0]));
+        let z_range = center.1.saturating_sub(distance).max(chunk_of(options.min[2]))
+            ..=center.1.saturating_add(distance).min(chunk_of(options.max[2]));
+
+        let mut chunks = self.chunks.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let count = chunks.grids.len();
+        chunks.grids.retain(|(x, z), _| x_range.contains(x) && z_range.contains(z));
+        let mut changed = chunks.grids.len() != count;
+
+        let mut result = Ok(());
+        for x in x_range {
+            for z in z_range.clone() {
+                if chunks.grids.contains_key(&(x, z)) {
+                    continue;
+                }
+                let grid = match self.load_chunk(x, z) {
+                    Ok(grid) => grid,
+                    Err(message) => {
+                        if result.is_ok() {
+                            result = Err(ImportError::new(&self.region_dir, format!("chunk {}, {}: {}", x, z, message)));
+                        }
+                        None
+                    }
+                };
+                chunks.grids.insert((x, z), grid.map(Arc::new));
+                changed = true;
+            }
+        }
+
+        if changed {
+            let grids: Vec<Box<dyn RayIntersect>> = chunks.grids.values()
+                .flatten()
+                .map(|grid| Box::new(Arc::clone(grid)) as Box<dyn RayIntersect>)
+                .collect();
+            chunks.bvh = Bvh::new(grids);
+        }
+        result.map(|()| changed)
+    }
+
+    // NBT de un chunk, o None si todavía no se ha generado
+    fn read_chunk(&self, x: i32, z: i32) -> Result<Option<Tag>, String> {
+        let path = self.region_dir.join(format!("r.{}.{}.mca", x.div_euclid(32), z.div_euclid(32)));
+        let Ok(mut file) = File::open(&path) else {
+            return Ok(None);
+        };
+        let io = |err: std::io::Error| err.to_string();
+
+        // La cabecera guarda por chunk 3 bytes de sector inicial y 1 de longitud
+        let mut location = [0u8; 4];
+        file.seek(SeekFrom::Start(4 * (x.rem_euclid(32) + 32 * z.rem_euclid(32)) as u64)).map_err(io)?;
+        file.read_exact(&mut location).map_err(io)?;
+        let sector = u32::from_be_bytes([0, location[0], location[1], location[2]]) as u64;
+        if sector == 0 {
+            return Ok(None);
+        }
+
+        let mut header = [0u8; 5];
+        file.seek(SeekFrom::Start(sector * SECTOR_SIZE)).map_err(io)?;
+        file.read_exact(&mut header).map_err(io)?;
+        let length = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as u64;
+        if length == 0 || length > location[3] as u64 * SECTOR_SIZE {
+            return Err("invalid chunk length".to_string());
+        }
+
+        // Los chunks muy grandes se guardan aparte, en c.X.Z.mcc
+        let compression = header[4];
+        let data = if compression & 0x80 != 0 {
+            std::fs::read(self.region_dir.join(format!("c.{}.{}.mcc", x, z))).map_err(io)?
+        } else {
+            let mut data = vec![0; length as usize - 1];
+            file.read_exact(&mut data).map_err(io)?;
+            data
+        };
+        match compression & 0x7f {
+            1..=3 => {}
+            4 => return Err("LZ4 compressed chunks are not supported".to_string()),
+            other => return Err(format!("unknown compression type {}", other)),
+        }
+
+        nbt::decompress(&data).and_then(|data| nbt::parse(&data)).map(Some)
+    }
+
+    // Rejilla con la parte del chunk que cae dentro de la caja, o None si no
+    // tiene ningún bloque visible
+    fn load_chunk(&self, chunk_x: i32, chunk_z: i32) -> Result<Option<VoxelGrid>, String> {
+        let Some(root) = self.read_chunk(chunk_x, chunk_z)? else {
+            return Ok(None);
+        };
+        let options = &self.options;
+        let spanning = root.get("DataVersion").and_then(Tag::as_i64).unwrap_or(0) < NON_SPANNING_DATA_VERSION;
+
+        // Hasta la 1.17 las secciones están dentro de "Level"
+        let level = root.get("Level").unwrap_or(&root);
+        let sections = level.get("sections").or_else(|| level.get("Sections"))
+            .and_then(Tag::as_list)
+            .unwrap_or(&[]);
+
+        let min_x = (chunk_x * 16).max(options.min[0]);
+        let max_x = (chunk_x * 16 + 15).min(options.max[0]);
+        let min_z = (chunk_z * 16).max(options.min[2]);
+        let max_z = (chunk_z * 16 + 15).min(options.max[2]);
+        let offset = Vec3::new((min_x - options.min[0]) as f32, 0.0, (min_z - options.min[2]) as f32);
+        let dims = [
+            (max_x - min_x + 1) as usize,
+            (options.max[1] - options.min[1] + 1) as usize,
+            (max_z - min_z + 1) as usize,
+        ];
+        let mut grid = VoxelGrid::new(options.origin + offset * options.block_size, options.block_size, dims, Vec::new())?;
+        let mut ids: HashMap<String, u16> = HashMap::new();
+        let mut empty = true;
+
+        for section in sections {
+            let Some((min_y, max_y)) = section.get("Y").and_then(Tag::as_i64)
+                .and_then(|section_y| section_rows(section_y, options.min[1], options.max[1]))
+            else {
+                continue;
+            };
+
+            let states = section.get("block_states");
+            let palette = states.and_then(|states| states.get("palette")).or_else(|| section.get("Palette"));
+            let data = states.and_then(|states| states.get("data")).or_else(|| section.get("BlockStates"));
+            let Some(palette) = palette.and_then(Tag::as_list) else {
+                if section.get("Blocks").is_some() {
+                    return Err("chunks saved before Minecraft 1.13 are not supported".to_string());
+                }
+                continue;
+            };
+
+            // Id de la rejilla de cada entrada de la paleta de la sección
+            let mut section_ids = Vec::with_capacity(palette.len());
+            for entry in palette {
+                let state = block_state(entry).ok_or("invalid palette entry")?;
+                let id = match ids.get(&state) {
+                    Some(&id) => id,
+                    None => {
+                        let id = match options.blocks.get(&state).map_err(|err| err.to_string())? {
+                            Some(faces) if grid.palette.len() < u16::MAX as usize - 1 => {
+                                grid.palette.push(faces);
+                                grid.palette.len() as u16
+                            }
+                            _ => 0,
+                        };
+                        ids.insert(state, id);
+                        id
+                    }
+                };
+                section_ids.push(id);
+            }
+            if section_ids.iter().all(|&id| id == 0) {
+                continue;
+            }
+
+            // Una paleta de un solo estado no guarda datos
+            let data: &[i64] = match data {
+                Some(Tag::LongArray(data)) => data,
+                _ if palette.len() == 1 => &[],
+                _ => return Err("section without block data".to_string()),
+            };
+            let bits = (usize::BITS - (palette.len() - 1).leading_zeros()).max(4) as usize;
+
+            for y in min_y..=max_y {
+                for z in min_z..=max_z {
+                    for x in min_x..=max_x {
+                        let value = if data.is_empty() {
+                            0
+                        } else {
+                            unpack(data, bits, section_index(x, y, z), spanning).ok_or("block data is too short")?
+                        };
+                        let id = *section_ids.get(value).ok_or("block with an invalid palette index")?;
+                        if id != 0 {
+                            grid.set((x - min_x) as usize, (y - options.min[1]) as usize, (z - min_z) as usize, id);
+                            empty = false;
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(if empty { None } else { Some(grid) })
+    }
+}
+
+impl RayIntersect for AnvilWorld {
+    fn ray_intersect(&self, ray_origin: &Vec3, ray_direction: &Vec3) -> Intersect {
+        match self.chunks.read() {
+            Ok(chunks) => chunks.bvh.ray_intersect(ray_origin, ray_direction),
+            Err(poisoned) => poisoned.into_inner().bvh.ray_intersect(ray_origin, ray_direction),
+        }
+    }
+
+    // La caja completa, aunque sólo parte de ella esté cargada
+    fn bounds(&self) -> Aabb {
+        let options = &self.options;
+        let size = Vec3::new(
+            (options.max[0] - options.min[0] + 1) as f32,
+            (options.max[1] - options.min[1] + 1) as f32,
+            (options.max[2] - options.min[2] + 1) as f32,
+        );
+        Aabb::new(options.origin, options.origin + size * options.block_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Valores 1 a 14 con 5 bits cada uno
+    const VALUES: [usize; 14] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14];
+    // Desde la 1.16: 12 valores por long y los 4 bits sobrantes a cero
+    const PADDED: [i64; 2] = [0x062d_4941_cc52_0c41, 0x1cd];
+    // Hasta la 1.15: el valor 13 empieza en el bit 60 del primer long y
+    // termina en el segundo
+    const SPANNING: [i64; 2] = [0xd62d_4941_cc52_0c41_u64 as i64, 0x1c];
+
+    fn world(min: [i32; 3], max: [i32; 3]) -> Result<AnvilWorld, ImportError> {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/worlds/hills");
+        AnvilWorld::open(&dir, WorldOptions::new(min, max))
+    }
+
+    #[test]
+    fn unpacks_padded_block_states() {
+        for (index, &value) in VALUES.iter().enumerate() {
+            assert_eq!(unpack(&PADDED, 5, index, false), Some(value), "index {}", index);
+        }
+        // Con 4 bits cada long guarda 16 valores, del bit bajo al alto
+        let nibbles = [0xfedc_ba98_7654_3210_u64 as i64];
+        for index in 0..16 {
+            assert_eq!(unpack(&nibbles, 4, index, false), Some(index));
+        }
+        assert_eq!(unpack(&PADDED, 5, 24, false), None);
+    }
+
+    #[test]
+    fn unpacks_spanning_block_states() {
+        for (index, &value) in VALUES.iter().enumerate() {
+            assert_eq!(unpack(&SPANNING, 5, index, true), Some(value), "index {}", index);
+        }
+        // Leídos sin cruzar longs, los mismos datos dan otro valor
+        assert_ne!(unpack(&SPANNING, 5, 12, false), Some(13));
+        assert_eq!(unpack(&SPANNING[..1], 5, 12, true), None);
+    }
+
+    #[test]
+    fn orders_section_data_by_y_z_x() {
+        assert_eq!(section_index(0, 0, 0), 0);
+        assert_eq!(section_index(1, 0, 0), 1);
+        assert_eq!(section_index(0, 0, 1), 16);
+        assert_eq!(section_index(0, 1, 0), 256);
+        assert_eq!(section_index(-1, -1, -1), 4095);
+        assert_eq!(section_index(17, 33, 18), section_index(1, 1, 2));
+    }
+
+    #[test]
+    fn clips_section_rows_to_bounds() {
+        assert_eq!(section_rows(0, 0, 31), Some((0, 15)));
+        assert_eq!(section_rows(1, 0, 20), Some((16, 20)));
+        assert_eq!(section_rows(-4, -64, 319), Some((-64, -49)));
+        assert_eq!(section_rows(2, 0, 31), None);
+        // Y corruptos: fuera de i32 o que desbordan al multiplicar por 16
+        assert_eq!(section_rows(i64::MAX, i32::MIN, i32::MAX), None);
+        assert_eq!(section_rows(i32::MAX as i64, i32::MIN, i32::MAX), None);
+        assert_eq!(section_rows(i32::MIN as i64, i32::MIN, i32::MAX), None);
+        assert_eq!(section_rows((i32::MAX / 16) as i64, 0, i32::MAX), Some((i32::MAX / 16 * 16, i32::MAX)));
+    }
+
+    #[test]
+    fn rejects_invalid_bounds() {
+        assert!(world([0, 0, 0], [15, 15, 15]).is_ok());
+        assert!(world([0, 10, 0], [15, 5, 15]).is_err());
+        assert!(world([i32::MIN, 0, 0], [i32::MAX, 15, 15]).is_err());
+        assert!(world([0, i32::MIN, 0], [15, i32::MAX, 15]).is_err());
+        assert!(world([0, 0, 0], [15, MAX_HEIGHT, 15]).is_err());
+    }
+}
@@ -0,0 +1,193 @@
+use nalgebra_glm::Vec3;
+
+use crate::aabb::Aabb;
+use crate::ray_intersect::{Intersect, RayIntersect};
+
+const MAX_LEAF_SIZE: usize = 4;
+const TRAVERSAL_COST: f32 = 1.0;
+
+struct BvhNode {
+    bounds: Aabb,
+    // Hoja: objetos [first, first + count). Interno: count == 0, el hijo
+    // izquierdo está justo después del nodo y el derecho en `first`.
+    first: usize,
+    count: usize,
+    axis: usize,
+}
+
+// Jerarquía de volúmenes envolventes construida con la heurística de área de
+// superficie (SAH). Los objetos sin límites finitos se prueban aparte.
+pub struct Bvh {
+    objects: Vec<Box<dyn RayIntersect>>,
+    unbounded: Vec<Box<dyn RayIntersect>>,
+    nodes: Vec<BvhNode>,
+    bounds: Aabb,
+}
+
+struct BuildItem {
+    bounds: Aabb,
+    center: Vec3,
+    index: usize,
+}
+
+impl Bvh {
+    pub fn new(objects: Vec<Box<dyn RayIntersect>>) -> Self {
+        let (bounded, unbounded): (Vec<_>, Vec<_>) = objects
+            .into_iter()
+            .partition(|object| object.bounds().is_finite());
+
+        let mut items: Vec<BuildItem> = bounded
+            .iter()
+            .enumerate()
+            .map(|(index, object)| {
+                let bounds = object.bounds();
+                BuildItem { bounds, center: bounds.center(), index }
+            })
+            .collect();
+
+        let mut nodes = Vec::new();
+        if !items.is_empty() {
+            build(&mut nodes, &mut items, 0);
+        }
+
+        // Reordenar los objetos para que cada hoja apunte a un rango contiguo
+        let mut slots: Vec<Option<Box<dyn RayIntersect>>> = bounded.into_iter().map(Some).collect();
+        let objects = items
+            .iter()
+            .map(|item| slots[item.index].take().expect("each object appears once in the BVH"))
+            .collect();
+
+        let bounds = if unbounded.is_empty() {
+            nodes.first().map_or(Aabb::empty(), |node: &BvhNode| node.bounds)
+        } else {
+            Aabb::infinite()
+        };
+
+        Bvh { objects, unbounded, nodes, bounds }
+    }
+}
+
+fn build(nodes: &mut Vec<BvhNode>, items: &mut [BuildItem], first: usize) -> usize {
+    let bounds = items.iter().fold(Aabb::empty(), |acc, item| acc.union(&item.bounds));
+    let node_index = nodes.len();
+    nodes.push(BvhNode { bounds, first, count: items.len(), axis: 0 });
+
+    if items.len() <= 1 {
+        return node_index;
+    }
+
+    let Some((axis, split)) = best_split(items, &bounds) else {
+        return node_index;
+    };
+
+    sort_by_axis(items, axis);
+    let (left, right) = items.split_at_mut(split);
+    build(nodes, left, first);
+    let right_index = build(nodes, right, first + split);
+
+    let node = &mut nodes[node_index];
+    node.first = right_index;
+    node.count = 0;
+    node.axis = axis;
+    node_index
+}
+
+fn sort_by_axis(items: &mut [BuildItem], axis: usize) {
+    items.sort_by(|a, b| a.center[axis].total_cmp(&b.center[axis]));
+}
+
+// Busca el corte con menor costo SAH probando cada posición en los tres ejes.
+// Devuelve None cuando dejar los objetos en una hoja sale más barato.
+fn best_split(items: &mut [BuildItem], bounds: &Aabb) -> Option<(usize, usize)> {
+    let parent_area = bounds.surface_area();
+    let count = items.len();
+    let mut best: Option<(usize, usize)> = None;
+    let mut best_cost = f32::INFINITY;
+    let mut right_areas = vec![0.0; count];
+
+    for axis in 0..3 {
+        sort_by_axis(items, axis);
+
+        let mut right = Aabb::empty();
+        for i in (1..count).rev() {
+            right = right.union(&items[i].bounds);
+            right_areas[i] = right.surface_area();
+        }
+
+        let mut left = Aabb::empty();
+        for split in 1..count {
+            left = left.union(&items[split - 1].bounds);
+            let cost = TRAVERSAL_COST
+                + if parent_area > 0.0 {
+                    (left.surface_area() * split as f32 + right_areas[split] * (count - split) as f32) / parent_area
+                } else {
+                    count as f32
+                };
+            if cost < best_cost {
+                best_cost = cost;
+                best = Some((axis, split));
+            }
+        }
+    }
+
+    if count <= MAX_LEAF_SIZE && best_cost >= count as f32 {
+        return None;
+    }
+    best
+}
+
+impl RayIntersect for Bvh {
+    fn ray_intersect(&self, ray_origin: &Vec3, ray_direction: &Vec3) -> Intersect {
+        let mut closest = Intersect::empty();
+        let mut zbuffer = f32::INFINITY;
+
+        for object in &self.unbounded {
+            let i = object.ray_intersect(ray_origin, ray_direction);
+            if i.is_intersecting && i.distance < zbuffer {
+                zbuffer = i.distance;
+                closest = i;
+            }
+        }
+
+        if self.nodes.is_empty() {
+            return closest;
+        }
+
+        let inv_direction = Vec3::new(1.0 / ray_direction.x, 1.0 / ray_direction.y, 1.0 / ray_direction.z);
+        let mut stack = vec![0];
+
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if node.bounds.ray_entry(ray_origin, &inv_direction, zbuffer).is_none() {
+                continue;
+            }
+
+            if node.count > 0 {
+                for object in &self.objects[node.first..node.first + node.count] {
+                    let i = object.ray_intersect(ray_origin, ray_direction);
+                    if i.is_intersecting && i.distance < zbuffer {
+                        zbuffer = i.distance;
+                        closest = i;
+                    }
+                }
+            } else if ray_direction[node.axis] < 0.0 {
+                // Visitar primero el hijo más cercano al origen del rayo
+                stack.push(index + 1);
+                stack.push(node.first);
+            } else {
+                stack.push(node.first);
+                stack.push(index + 1);
+            }
+        }
+
+        closest
+    }
+
+    fn bounds(&self) -> Aabb {
+        self.bounds
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        self.objects.iter().chain(&self.unbounded).try_for_each(|object| object.validate())
+    }
+}
@@ -2,10 +2,14 @@
 use nalgebra_glm::Vec3;
 use std::f32::consts::PI;
 
+// Campo de visión vertical por defecto, en radianes
+pub const DEFAULT_FOV: f32 = PI / 3.0;
+
 pub struct Camera {
     pub eye: Vec3,
     pub center: Vec3,
-    pub up: Vec3
+    pub up: Vec3,
+    pub fov: f32,
 }
 
 impl Camera {
@@ -13,7 +17,8 @@ impl Camera {
         Camera {
             eye,
             center,
-            up
+            up,
+            fov: DEFAULT_FOV,
         }
     }
 
@@ -24,7 +29,7 @@ impl Camera {
 
         let rotated = vector.x * right + vector.y * up - vector.z * forward;
 
-        return rotated.normalize();
+        rotated.normalize()
     }
 
     pub fn orbit(&mut self, delta_yaw: f32, delta_pitch: f32) {
@@ -50,4 +55,19 @@ impl Camera {
         let forward = (self.center - self.eye).normalize();
         self.eye += forward * amount;
     }
+
+    // Desplaza la cámara y el punto que mira sobre el plano horizontal, hacia
+    // la derecha y hacia adelante según la dirección de la vista
+    pub fn pan(&mut self, right: f32, forward: f32) {
+        let mut ahead = self.center - self.eye;
+        ahead.y = 0.0;
+        if ahead.magnitude() < 1e-6 {
+            return;
+        }
+        let ahead = ahead.normalize();
+        let side = Vec3::new(-ahead.z, 0.0, ahead.x);
+        let offset = side * right + ahead * forward;
+        self.eye += offset;
+        self.center += offset;
+    }
 }
\ No newline at end of file
@@ -0,0 +1,126 @@
+use std::fmt;
+use std::str::FromStr;
+
+use raytracing::scene_file::DEFAULT_SCENE;
+use raytracing::{OutputTransform, RenderSettings};
+
+pub const USAGE: &str = "\
+Usage: Raytracing [OPTIONS]
+
+Options:
+      --scene <FILE>    Scene description (.toml) or glTF model (.gltf, .glb) to
+                        render (default scenes/diorama.toml)
+  -o, --output <FILE>   Render one frame without a window and save it as PNG
+      --width <PIXELS>  Framebuffer width (default 800)
+      --height <PIXELS> Framebuffer height (default 600)
+      --threads <N>     Render threads, 0 uses every core (default 0)
+      --spp <N>         Samples per pixel (default 1)
+      --filter <NAME>   box, tent, gaussian or mitchell (default box)
+      --integrator <NAME>
+                        whitted, path, ao, normals, depth, albedo or uv
+                        (default whitted)
+      --max-depth <N>   Bounce limit of the integrator (default 3 for whitted,
+                        16 for path)
+      --exposure <EV>   Exposure adjustment in stops (default 0)
+      --tonemap <NAME>  clamp, reinhard, aces or uncharted2 (default clamp)
+      --linear          Skip the sRGB encoding of the output
+      --night           Start with the night lighting
+  -h, --help            Print this help";
+
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub scene: String,
+    pub output: Option<String>,
+    pub width: usize,
+    pub height: usize,
+    pub render_settings: RenderSettings,
+    pub output_transform: OutputTransform,
+    pub is_day: bool,
+    pub help: bool,
+}
+
+#[derive(Debug)]
+pub struct ArgError(pub String);
+
+impl fmt::Display for ArgError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            scene: DEFAULT_SCENE.to_string(),
+            output: None,
+            width: 800,
+            height: 600,
+            render_settings: RenderSettings::default(),
+            output_transform: OutputTransform::default(),
+            is_day: true,
+            help: false,
+        }
+    }
+}
+
+impl Options {
+    pub fn parse<I: Iterator<Item = String>>(mut args: I) -> Result<Self, ArgError> {
+        let mut options = Options::default();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--scene" => options.scene = value(&arg, args.next())?,
+                "-o" | "--output" => options.output = Some(value(&arg, args.next())?),
+                "--width" => options.width = positive(&arg, args.next())?,
+                "--height" => options.height = positive(&arg, args.next())?,
+                "--threads" => options.render_settings.threads = count(&arg, args.next())?,
+                "--spp" => options.render_settings.samples_per_pixel = positive(&arg, args.next())?,
+                "--filter" => {
+                    options.render_settings.filter = value(&arg, args.next())?.parse().map_err(ArgError)?
+                }
+                "--integrator" => {
+                    options.render_settings.integrator = value(&arg, args.next())?.parse().map_err(ArgError)?
+                }
+                "--max-depth" => options.render_settings.max_depth = Some(positive(&arg, args.next())?),
+                "--exposure" => options.output_transform.exposure = number(&arg, args.next())?,
+                "--tonemap" => {
+                    options.output_transform.tone_mapper = value(&arg, args.next())?.parse().map_err(ArgError)?
+                }
+                "--linear" => options.output_transform.srgb = false,
+                "--night" => options.is_day = false,
+                "-h" | "--help" => options.help = true,
+                _ => return Err(ArgError(format!("unknown argument '{}'", arg))),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+fn value(flag: &str, value: Option<String>) -> Result<String, ArgError> {
+    value.ok_or_else(|| ArgError(format!("missing value for '{}'", flag)))
+}
+
+// Entero mayor que cero del tipo que pide el campo, así un valor que no cabe
+// (por ejemplo 2^32 en un u32) es un error y no se trunca
+fn positive<T: FromStr + PartialOrd + Default>(flag: &str, raw: Option<String>) -> Result<T, ArgError> {
+    let raw = value(flag, raw)?;
+    match raw.parse::<T>() {
+        Ok(n) if n > T::default() => Ok(n),
+        _ => Err(ArgError(format!("invalid value '{}' for '{}': expected a positive integer", raw, flag))),
+    }
+}
+
+fn count(flag: &str, raw: Option<String>) -> Result<usize, ArgError> {
+    let raw = value(flag, raw)?;
+    raw.parse::<usize>()
+        .map_err(|_| ArgError(format!("invalid value '{}' for '{}': expected a non-negative integer", raw, flag)))
+}
+
+fn number(flag: &str, raw: Option<String>) -> Result<f32, ArgError> {
+    let raw = value(flag, raw)?;
+    match raw.parse::<f32>() {
+        Ok(n) if n.is_finite() => Ok(n),
+        _ => Err(ArgError(format!("invalid value '{}' for '{}': expected a number", raw, flag))),
+    }
+}
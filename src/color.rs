@@ -1,30 +1,41 @@
 use std::fmt;
 
-#[derive(Debug, Clone, Copy)]
+use crate::tonemap::srgb_to_linear;
+
+// Radiancia RGB lineal en punto flotante. Los canales no se limitan a [0, 1]
+// durante el sombreado; solo se convierten a 8 bits al escribir el framebuffer.
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Color {
-    r: u8,
-    g: u8,
-    b: u8,
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
 }
 
 impl Color {
-    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+    pub const fn new(r: f32, g: f32, b: f32) -> Self {
         Color { r, g, b }
     }
 
-    pub const fn from_hex(hex: u32) -> Self {
-        let r = ((hex >> 16) & 0xFF) as u8;
-        let g = ((hex >> 8) & 0xFF) as u8;
-        let b = (hex & 0xFF) as u8;
-        Color { r, g, b }
+    // Los colores de 8 bits (texturas, archivos de escena) vienen en sRGB
+    pub fn from_srgb8(r: u8, g: u8, b: u8) -> Self {
+        Color {
+            r: srgb_to_linear(r as f32 / 255.0),
+            g: srgb_to_linear(g as f32 / 255.0),
+            b: srgb_to_linear(b as f32 / 255.0),
+        }
+    }
+
+    pub fn from_hex(hex: u32) -> Self {
+        Color::from_srgb8((hex >> 16) as u8, (hex >> 8) as u8, hex as u8)
     }
 
     pub const fn black() -> Self {
-        Color { r: 0, g: 0, b: 0 }
+        Color { r: 0.0, g: 0.0, b: 0.0 }
     }
 
-    pub fn to_hex(&self) -> u32 {
-        ((self.r as u32) << 16) | ((self.g as u32) << 8) | (self.b as u32)
+    pub fn to_hex(self) -> u32 {
+        let channel = |c: f32| (c * 255.0).round().clamp(0.0, 255.0) as u32;
+        (channel(self.r) << 16) | (channel(self.g) << 8) | channel(self.b)
     }
 }
 
@@ -35,9 +46,9 @@ impl Add for Color {
 
     fn add(self, other: Color) -> Color {
         Color {
-            r: self.r.saturating_add(other.r),
-            g: self.g.saturating_add(other.g),
-            b: self.b.saturating_add(other.b),
+            r: self.r + other.r,
+            g: self.g + other.g,
+            b: self.b + other.b,
         }
     }
 }
@@ -49,9 +60,21 @@ impl Mul<f32> for Color {
 
     fn mul(self, scalar: f32) -> Color {
         Color {
-            r: (self.r as f32 * scalar).clamp(0.0, 255.0) as u8,
-            g: (self.g as f32 * scalar).clamp(0.0, 255.0) as u8,
-            b: (self.b as f32 * scalar).clamp(0.0, 255.0) as u8,
+            r: self.r * scalar,
+            g: self.g * scalar,
+            b: self.b * scalar,
+        }
+    }
+}
+
+impl Mul<Color> for Color {
+    type Output = Color;
+
+    fn mul(self, other: Color) -> Color {
+        Color {
+            r: self.r * other.r,
+            g: self.g * other.g,
+            b: self.b * other.b,
         }
     }
 }
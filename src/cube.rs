@@ -1,13 +1,27 @@
 use nalgebra_glm::Vec3;
 use crate::ray_intersect::{RayIntersect, Intersect};
-use crate::material::Material;
-use std::rc::Rc;
+use crate::material::{BlockFaces, Material};
+use crate::aabb::Aabb;
+use crate::voxel_grid::block_face_uv;
+use std::sync::Arc;
 
+// Cubo alineado con los ejes. Cada cara puede tener su propio material y su
+// textura se orienta igual que en los bloques de Minecraft.
 #[derive(Debug)]
 pub struct Cube {
     pub center: Vec3,
     pub size: f32,
-    pub material: Rc<Material>, 
+    pub faces: BlockFaces,
+}
+
+impl Cube {
+    pub fn new(center: Vec3, size: f32, material: Arc<Material>) -> Self {
+        Cube::with_faces(center, size, BlockFaces::uniform(material))
+    }
+
+    pub fn with_faces(center: Vec3, size: f32, faces: BlockFaces) -> Self {
+        Cube { center, size, faces }
+    }
 }
 
 impl RayIntersect for Cube {
@@ -57,10 +71,6 @@ impl RayIntersect for Cube {
             t_min = tz_min;
         }
 
-        if tz_max < t_max {
-            t_max = tz_max;
-        }
-
         if t_min < 0.0 {
             return Intersect::empty();
         }
@@ -83,7 +93,21 @@ impl RayIntersect for Cube {
             normal = Vec3::new(0.0, 0.0, 1.0);
         }
 
-        // Retornar la intersección
-        Intersect::new(point, normal, t_min, self.material.clone())
+        // Retornar la intersección con la UV relativa a la cara
+        let local = (point - min) / self.size;
+        Intersect::new(point, normal, t_min, Arc::clone(self.faces.facing(&normal)), block_face_uv(&normal, &local))
+            .with_uv_density(1.0 / self.size)
+    }
+
+    fn bounds(&self) -> Aabb {
+        let half_size = Vec3::repeat(self.size / 2.0);
+        Aabb::new(self.center - half_size, self.center + half_size)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if !(self.size > 0.0 && self.size.is_finite()) {
+            return Err(format!("cube size must be positive and finite, got {}", self.size));
+        }
+        Ok(())
     }
 }
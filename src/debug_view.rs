@@ -0,0 +1,55 @@
+use nalgebra_glm::Vec3;
+
+use crate::color::Color;
+use crate::integrator::{closest_intersect, surface_color, Integrator};
+use crate::ray_differential::RayDifferential;
+use crate::sampler::Sampler;
+use crate::scene::Scene;
+
+// Distancia a la que la vista de profundidad llega a la mitad de gris
+const DEPTH_HALF_DISTANCE: f32 = 5.0;
+
+// Vistas de depuración que muestran un solo dato del primer impacto, sin luces
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DebugView {
+    // Normal del impacto llevada de [-1, 1] a [0, 1]
+    Normals,
+    // Más claro cuanto más cerca de la cámara
+    Depth,
+    // Color difuso o de textura, sin sombrear
+    Albedo,
+    // Coordenadas de textura en los canales rojo y verde
+    Uv,
+}
+
+impl Integrator for DebugView {
+    fn radiance(
+        &self,
+        scene: &Scene,
+        ray_origin: &Vec3,
+        ray_direction: &Vec3,
+        differential: &RayDifferential,
+        _sampler: &mut Sampler,
+    ) -> Color {
+        let intersect = closest_intersect(ray_origin, ray_direction, &scene.objects);
+        if !intersect.is_intersecting {
+            return Color::black();
+        }
+
+        match self {
+            DebugView::Normals => {
+                let n = intersect.normal * 0.5 + Vec3::new(0.5, 0.5, 0.5);
+                Color::new(n.x, n.y, n.z)
+            }
+            DebugView::Depth => {
+                let shade = DEPTH_HALF_DISTANCE / (DEPTH_HALF_DISTANCE + intersect.distance);
+                Color::new(shade, shade, shade)
+            }
+            DebugView::Albedo => surface_color(&intersect, differential.footprint(ray_direction, &intersect)),
+            DebugView::Uv => {
+                let (u, v) = intersect.uv;
+                Color::new(u, v, 0.0)
+            }
+        }
+    }
+}
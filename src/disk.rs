@@ -0,0 +1,68 @@
+use nalgebra_glm::Vec3;
+use std::sync::Arc;
+use crate::aabb::Aabb;
+use crate::material::Material;
+use crate::plane::{plane_distance, tangent_frame};
+use crate::ray_intersect::{RayIntersect, Intersect};
+
+// Círculo plano. La textura se ajusta al cuadrado que lo envuelve.
+pub struct Disk {
+    pub center: Vec3,
+    pub normal: Vec3,
+    pub radius: f32,
+    pub material: Arc<Material>,
+}
+
+impl Disk {
+    pub fn new(center: Vec3, normal: Vec3, radius: f32, material: Arc<Material>) -> Self {
+        Disk {
+            center,
+            normal: normal.normalize(),
+            radius,
+            material,
+        }
+    }
+}
+
+impl RayIntersect for Disk {
+    fn ray_intersect(&self, ray_origin: &Vec3, ray_direction: &Vec3) -> Intersect {
+        let Some(t) = plane_distance(&self.center, &self.normal, ray_origin, ray_direction) else {
+            return Intersect::empty();
+        };
+
+        let point = ray_origin + ray_direction * t;
+        let local = point - self.center;
+        if local.magnitude_squared() > self.radius * self.radius {
+            return Intersect::empty();
+        }
+
+        let (tangent, bitangent) = tangent_frame(&self.normal);
+        let scale = 0.5 / self.radius;
+        let uv = (0.5 + local.dot(&tangent) * scale, 0.5 - local.dot(&bitangent) * scale);
+
+        Intersect::new(point, self.normal, t, Arc::clone(&self.material), uv).with_uv_density(scale)
+    }
+
+    fn bounds(&self) -> Aabb {
+        // Semiancho del disco a lo largo de cada eje del mundo
+        let n = self.normal;
+        let extent = Vec3::new(
+            (1.0 - n.x * n.x).max(0.0).sqrt(),
+            (1.0 - n.y * n.y).max(0.0).sqrt(),
+            (1.0 - n.z * n.z).max(0.0).sqrt(),
+        ) * self.radius;
+        Aabb::new(self.center - extent, self.center + extent)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        // `new` normaliza la normal, así que una normal nula queda en NaN
+        let length = self.normal.magnitude();
+        if !(length > 0.0 && length.is_finite()) {
+            return Err("disk normal must be non-zero and finite".to_string());
+        }
+        if !(self.radius > 0.0 && self.radius.is_finite()) {
+            return Err(format!("disk radius must be positive and finite, got {}", self.radius));
+        }
+        Ok(())
+    }
+}
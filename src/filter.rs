@@ -0,0 +1,208 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::color::Color;
+
+// Filtro de reconstrucción: cada muestra de un píxel pesa según su distancia
+// al centro del píxel, medida en píxeles.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PixelFilter {
+    Box,
+    Tent,
+    Gaussian,
+    Mitchell,
+}
+
+impl PixelFilter {
+    pub const ALL: [PixelFilter; 4] = [
+        PixelFilter::Box,
+        PixelFilter::Tent,
+        PixelFilter::Gaussian,
+        PixelFilter::Mitchell,
+    ];
+
+    // Radio del soporte del filtro en píxeles
+    pub fn radius(self) -> f32 {
+        match self {
+            PixelFilter::Box => 0.5,
+            PixelFilter::Tent => 1.0,
+            PixelFilter::Gaussian => 1.5,
+            PixelFilter::Mitchell => 2.0,
+        }
+    }
+
+    // Todos los filtros son separables: w(dx, dy) = w(dx) · w(dy)
+    pub fn weight(self, d: f32) -> f32 {
+        match self {
+            PixelFilter::Box => 1.0,
+            PixelFilter::Tent => (1.0 - d.abs()).max(0.0),
+            PixelFilter::Gaussian => gaussian(d, self.radius()),
+            PixelFilter::Mitchell => mitchell(d / self.radius()),
+        }
+    }
+}
+
+const TABLE_SIZE: usize = 64;
+
+// Tabla para elegir desplazamientos con probabilidad proporcional a |w|. Cada
+// muestra cae en un lóbulo positivo o negativo del filtro; `FilterSum` promedia
+// cada lóbulo por separado y los combina con el área que tienen.
+pub struct FilterTable {
+    radius: f32,
+    cdf: Vec<f32>,
+    // Signo del filtro en cada intervalo de la tabla
+    positive: Vec<bool>,
+    // Integrales en 2D de la parte positiva y de la negativa (en valor absoluto)
+    positive_area: f32,
+    negative_area: f32,
+}
+
+impl FilterTable {
+    pub fn new(filter: PixelFilter) -> Self {
+        let radius = filter.radius();
+        let bin_width = 2.0 * radius / TABLE_SIZE as f32;
+        let values: Vec<f32> = (0..TABLE_SIZE)
+            .map(|i| filter.weight(-radius + (i as f32 + 0.5) * bin_width))
+            .collect();
+        let total: f32 = values.iter().map(|value| value.abs()).sum::<f32>() * bin_width;
+
+        let mut cdf = Vec::with_capacity(TABLE_SIZE + 1);
+        let mut acc = 0.0;
+        cdf.push(0.0);
+        for value in &values {
+            acc += value.abs() * bin_width / total;
+            cdf.push(acc);
+        }
+
+        // Como w(dx, dy) = w(dx) · w(dy), el peso es positivo cuando los dos
+        // ejes tienen el mismo signo
+        let positive_1d: f32 = values.iter().filter(|&&value| value >= 0.0).sum::<f32>() * bin_width;
+        let negative_1d = total - positive_1d;
+
+        FilterTable {
+            radius,
+            cdf,
+            positive: values.iter().map(|&value| value >= 0.0).collect(),
+            positive_area: positive_1d * positive_1d + negative_1d * negative_1d,
+            negative_area: 2.0 * positive_1d * negative_1d,
+        }
+    }
+
+    // Convierte u en [0, 1) en un desplazamiento en un eje y el signo del
+    // filtro en ese punto
+    fn sample_1d(&self, u: f32) -> (f32, bool) {
+        let bin = self.cdf.partition_point(|&c| c <= u).clamp(1, TABLE_SIZE) - 1;
+        let span = self.cdf[bin + 1] - self.cdf[bin];
+        let t = if span > 0.0 { (u - self.cdf[bin]) / span } else { 0.5 };
+        let bin_width = 2.0 * self.radius / TABLE_SIZE as f32;
+        let d = -self.radius + (bin as f32 + t) * bin_width;
+        (d, self.positive[bin])
+    }
+
+    // Desplazamiento (dx, dy) y si el filtro es positivo allí
+    pub fn sample(&self, u: (f32, f32)) -> (f32, f32, bool) {
+        let (dx, positive_x) = self.sample_1d(u.0);
+        let (dy, positive_y) = self.sample_1d(u.1);
+        (dx, dy, positive_x == positive_y)
+    }
+}
+
+// Muestras de un píxel separadas según el signo del filtro. El resultado es
+// (A+ · media+ - A- · media-) / (A+ - A-), con A± el área de cada parte del
+// filtro. Dividir por la suma de pesos de cada píxel, en cambio, da valores
+// cercanos a cero o negativos con pocas muestras si el filtro tiene lóbulos
+// negativos.
+#[derive(Debug, Clone, Copy)]
+pub struct FilterSum {
+    positive: Color,
+    positive_count: u32,
+    negative: Color,
+    negative_count: u32,
+}
+
+impl FilterSum {
+    pub fn new() -> Self {
+        FilterSum {
+            positive: Color::black(),
+            positive_count: 0,
+            negative: Color::black(),
+            negative_count: 0,
+        }
+    }
+
+    pub fn add(&mut self, color: Color, positive: bool) {
+        if positive {
+            self.positive = self.positive + color;
+            self.positive_count += 1;
+        } else {
+            self.negative = self.negative + color;
+            self.negative_count += 1;
+        }
+    }
+
+    pub fn resolve(&self, table: &FilterTable) -> Color {
+        let mean = |sum: Color, count: u32| (count > 0).then(|| sum * (1.0 / count as f32));
+        let positive = mean(self.positive, self.positive_count);
+        let negative = mean(self.negative, self.negative_count);
+        // Si un lóbulo aún no tiene muestras se usa la media del otro, de
+        // modo que una radiancia constante da siempre el mismo valor
+        match (positive, negative) {
+            (Some(positive), Some(negative)) => {
+                let scale = 1.0 / (table.positive_area - table.negative_area);
+                positive * (table.positive_area * scale) + negative * (-table.negative_area * scale)
+            }
+            (Some(mean), None) | (None, Some(mean)) => mean,
+            (None, None) => Color::black(),
+        }
+    }
+}
+
+impl Default for FilterSum {
+    fn default() -> Self {
+        FilterSum::new()
+    }
+}
+
+fn gaussian(d: f32, radius: f32) -> f32 {
+    const ALPHA: f32 = 2.0;
+    ((-ALPHA * d * d).exp() - (-ALPHA * radius * radius).exp()).max(0.0)
+}
+
+// Mitchell-Netravali con B = C = 1/3, sobre x normalizado a [-1, 1]
+fn mitchell(x: f32) -> f32 {
+    const B: f32 = 1.0 / 3.0;
+    const C: f32 = 1.0 / 3.0;
+    let x = (2.0 * x).abs();
+    if x > 2.0 {
+        0.0
+    } else if x > 1.0 {
+        ((-B - 6.0 * C) * x * x * x + (6.0 * B + 30.0 * C) * x * x + (-12.0 * B - 48.0 * C) * x + (8.0 * B + 24.0 * C)) / 6.0
+    } else {
+        ((12.0 - 9.0 * B - 6.0 * C) * x * x * x + (-18.0 + 12.0 * B + 6.0 * C) * x * x + (6.0 - 2.0 * B)) / 6.0
+    }
+}
+
+impl fmt::Display for PixelFilter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            PixelFilter::Box => "box",
+            PixelFilter::Tent => "tent",
+            PixelFilter::Gaussian => "gaussian",
+            PixelFilter::Mitchell => "mitchell",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for PixelFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PixelFilter::ALL
+            .iter()
+            .copied()
+            .find(|filter| filter.to_string() == s)
+            .ok_or_else(|| format!("unknown filter '{}', expected box, tent, gaussian or mitchell", s))
+    }
+}
+
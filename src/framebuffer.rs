@@ -1,8 +1,18 @@
+use image::{ImageFormat, ImageResult, RgbImage};
+
+use crate::color::Color;
+use crate::filter::FilterSum;
+use crate::tonemap::OutputTransform;
 
 pub struct Framebuffer {
     pub width: usize,
     pub height: usize,
     pub buffer: Vec<u32>,
+    // Radiancia lineal que escribe el renderizador antes de pasar a 8 bits
+    pub radiance: Vec<Color>,
+    // Muestras acumuladas del render progresivo
+    pub accumulation: Vec<FilterSum>,
+    pub accumulated_frames: u32,
     background_color: u32,
     current_color: u32,
 }
@@ -13,6 +23,9 @@ impl Framebuffer {
             width,
             height,
             buffer: vec![0; width * height],
+            radiance: vec![Color::black(); width * height],
+            accumulation: vec![FilterSum::new(); width * height],
+            accumulated_frames: 0,
             background_color: 0x000000,
             current_color: 0xFFFFFF
         }
@@ -37,4 +50,24 @@ impl Framebuffer {
     pub fn set_current_color(&mut self, color: u32) {
         self.current_color = color;
     }
+
+    pub fn reset_accumulation(&mut self) {
+        self.radiance.fill(Color::black());
+        self.accumulation.fill(FilterSum::new());
+        self.accumulated_frames = 0;
+    }
+
+    pub fn resolve(&mut self, transform: &OutputTransform) {
+        for (pixel, radiance) in self.buffer.iter_mut().zip(self.radiance.iter()) {
+            *pixel = transform.apply(*radiance);
+        }
+    }
+
+    pub fn save(&self, path: &str) -> ImageResult<()> {
+        let mut image = RgbImage::new(self.width as u32, self.height as u32);
+        for (pixel, &hex) in image.pixels_mut().zip(self.buffer.iter()) {
+            pixel.0 = [(hex >> 16) as u8, (hex >> 8) as u8, hex as u8];
+        }
+        image.save_with_format(path, ImageFormat::Png)
+    }
 }
@@ -0,0 +1,293 @@
+use base64::Engine;
+use nalgebra_glm::{Mat4, Vec3, Vec4};
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::camera::Camera;
+use crate::color::Color;
+use crate::import::ImportError;
+use crate::light::{DirectionalLight, LightSource, PointLight, SpotLight};
+use crate::material::Material;
+use crate::mesh::{normal_matrix, transform_point, Mesh};
+use crate::texture::{FilterMode, Texture, WrapMode};
+use crate::triangle::Triangle;
+
+// Índice de refracción cuando el material no usa KHR_materials_ior
+const DEFAULT_IOR: f32 = 1.5;
+// Atenuación física de las luces puntuales y focos de glTF
+const INVERSE_SQUARE: [f32; 3] = [0.0, 0.0, 1.0];
+
+// Contenido de un archivo glTF/GLB ya llevado al espacio de la escena. Las
+// luces se comparten con Arc para poder usarlas de día y de noche.
+pub struct GltfImport {
+    pub meshes: Vec<Mesh>,
+    pub cameras: Vec<Camera>,
+    pub lights: Vec<Arc<dyn LightSource>>,
+}
+
+struct Loader<'a> {
+    path: &'a Path,
+    buffers: Vec<Vec<u8>>,
+    images: Vec<Option<Arc<Texture>>>,
+}
+
+// Contenido de una URI: incrustado en base64 o en un archivo junto al glTF
+fn read_uri(path: &Path, uri: &str) -> Result<Vec<u8>, ImportError> {
+    if let Some(data) = uri.strip_prefix("data:") {
+        let (_, encoded) = data.split_once(";base64,")
+            .ok_or_else(|| ImportError::new(path, "only base64 data URIs are supported"))?;
+        return base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(|err| ImportError::new(path, format!("invalid base64 data: {}", err)));
+    }
+
+    let file = path.parent().unwrap_or(Path::new(".")).join(uri.replace("%20", " "));
+    std::fs::read(&file).map_err(|err| ImportError::new(path, format!("could not read '{}': {}", file.display(), err)))
+}
+
+impl Loader<'_> {
+    // Textura con el filtro y el modo de repetición de su sampler. La imagen
+    // decodificada se comparte entre las texturas que la usan.
+    fn texture(&mut self, texture: gltf::Texture) -> Result<Arc<Texture>, ImportError> {
+        use gltf::texture::{MagFilter, MinFilter, WrappingMode};
+
+        let sampler = texture.sampler();
+        let filter = match (sampler.mag_filter(), sampler.min_filter()) {
+            (Some(MagFilter::Nearest), _) => FilterMode::Nearest,
+            (_, Some(MinFilter::Nearest)) | (_, Some(MinFilter::Linear)) => FilterMode::Bilinear,
+            _ => FilterMode::Trilinear,
+        };
+        // El modelo sólo tiene un modo para los dos ejes; se usa el de u
+        let wrap = match sampler.wrap_s() {
+            WrappingMode::Repeat => WrapMode::Repeat,
+            WrappingMode::MirroredRepeat => WrapMode::Mirror,
+            WrappingMode::ClampToEdge => WrapMode::Clamp,
+        };
+
+        let image = self.image(texture.source())?;
+        Ok(Arc::new((*image).clone().with_sampling(filter, wrap)))
+    }
+
+    fn image(&mut self, image: gltf::Image) -> Result<Arc<Texture>, ImportError> {
+        if let Some(texture) = &self.images[image.index()] {
+            return Ok(Arc::clone(texture));
+        }
+
+        let bytes = match image.source() {
+            gltf::image::Source::View { view, .. } => {
+                let buffer = &self.buffers[view.buffer().index()];
+                view.offset().checked_add(view.length())
+                    .and_then(|end| buffer.get(view.offset()..end))
+                    .ok_or_else(|| ImportError::new(self.path, format!("image {} points outside its buffer", image.index())))?
+                    .to_vec()
+            }
+            gltf::image::Source::Uri { uri, .. } => read_uri(self.path, uri)?,
+        };
+        let decoded = image::load_from_memory(&bytes)
+            .map_err(|err| ImportError::new(self.path, format!("could not decode image {}: {}", image.index(), err)))?;
+
+        let texture = Arc::new(Texture::from_image(decoded));
+        self.images[image.index()] = Some(Arc::clone(&texture));
+        Ok(texture)
+    }
+
+    // Aproxima el modelo metálico-rugoso con el material de la escena: la
+    // rugosidad fija el exponente especular, los metales pulidos reflejan como
+    // espejos y la transmisión se vuelve transparencia.
+    fn material(&mut self, material: gltf::Material) -> Result<Material, ImportError> {
+        let pbr = material.pbr_metallic_roughness();
+        let [r, g, b, _] = pbr.base_color_factor();
+        let metallic = pbr.metallic_factor().clamp(0.0, 1.0);
+        let roughness = pbr.roughness_factor().clamp(0.0, 1.0);
+
+        let alpha = (roughness * roughness).max(1e-3);
+        let specular = (2.0 / (alpha * alpha) - 2.0).clamp(1.0, 1000.0);
+
+        let reflectivity = metallic * (1.0 - roughness);
+        let transmission = material.transmission().map_or(0.0, |t| t.transmission_factor());
+        let transparency = (transmission * (1.0 - metallic)).clamp(0.0, 1.0 - reflectivity);
+
+        let [er, eg, eb] = material.emissive_factor();
+        let emissive = Color::new(er, eg, eb) * material.emissive_strength().unwrap_or(1.0);
+
+        // glTF multiplica la textura por base_color_factor; la escena usa la
+        // textura en lugar del color difuso, así que el factor va en la textura
+        let texture = match pbr.base_color_texture() {
+            Some(info) => {
+                let texture = self.texture(info.texture())?;
+                if [r, g, b] == [1.0; 3] {
+                    Some(texture)
+                } else {
+                    Some(Arc::new(texture.tinted(Color::new(r, g, b))))
+                }
+            }
+            None => None,
+        };
+
+        Ok(Material::new(
+            Color::new(r, g, b),
+            specular,
+            [1.0 - metallic, 0.04 + 0.96 * metallic, reflectivity, transparency],
+            material.ior().unwrap_or(DEFAULT_IOR),
+            texture,
+            emissive,
+        ))
+    }
+
+    fn mesh(&self, mesh: gltf::Mesh, transform: &Mat4, materials: &[Arc<Material>], default_material: &Arc<Material>) -> Vec<Triangle> {
+        let normals_to_world = normal_matrix(transform);
+        let mut triangles = Vec::new();
+
+        for primitive in mesh.primitives() {
+            if primitive.mode() != gltf::mesh::Mode::Triangles {
+                continue;
+            }
+            let reader = primitive.reader(|buffer| self.buffers.get(buffer.index()).map(Vec::as_slice));
+            let Some(positions) = reader.read_positions() else {
+                continue;
+            };
+
+            let positions: Vec<Vec3> = positions.map(|p| transform_point(transform, &Vec3::from(p))).collect();
+            let normals: Option<Vec<Vec3>> = reader.read_normals()
+                .map(|normals| normals.map(|n| (normals_to_world * Vec3::from(n)).normalize()).collect());
+            let uvs: Option<Vec<(f32, f32)>> = reader.read_tex_coords(0)
+                .map(|uvs| uvs.into_f32().map(|[u, v]| (u, v)).collect());
+            let indices: Vec<usize> = match reader.read_indices() {
+                Some(indices) => indices.into_u32().map(|i| i as usize).collect(),
+                None => (0..positions.len()).collect(),
+            };
+            let material = primitive.material().index()
+                .and_then(|index| materials.get(index))
+                .unwrap_or(default_material);
+
+            for face in indices.chunks_exact(3) {
+                let [a, b, c] = [face[0], face[1], face[2]];
+                if a.max(b).max(c) >= positions.len() {
+                    continue;
+                }
+                let mut triangle = Triangle::new(positions[a], positions[b], positions[c], Arc::clone(material));
+                if let Some(normals) = normals.as_ref().filter(|n| n.len() == positions.len()) {
+                    triangle = triangle.with_normals([normals[a], normals[b], normals[c]]);
+                }
+                if let Some(uvs) = uvs.as_ref().filter(|uv| uv.len() == positions.len()) {
+                    triangle = triangle.with_uvs([uvs[a], uvs[b], uvs[c]]);
+                }
+                triangles.push(triangle);
+            }
+        }
+
+        triangles
+    }
+}
+
+fn direction(transform: &Mat4, local: Vec3) -> Vec3 {
+    let d = transform * Vec4::new(local.x, local.y, local.z, 0.0);
+    Vec3::new(d.x, d.y, d.z).normalize()
+}
+
+fn light(light: gltf::khr_lights_punctual::Light, transform: &Mat4) -> Arc<dyn LightSource> {
+    let [r, g, b] = light.color();
+    let color = Color::new(r, g, b);
+    let position = transform_point(transform, &Vec3::zeros());
+    // Las luces de glTF apuntan hacia -Z local
+    let forward = direction(transform, Vec3::new(0.0, 0.0, -1.0));
+
+    match light.kind() {
+        gltf::khr_lights_punctual::Kind::Directional => {
+            Arc::new(DirectionalLight::new(forward, color, light.intensity()))
+        }
+        gltf::khr_lights_punctual::Kind::Point => {
+            let mut point = PointLight::new(position, color, light.intensity());
+            point.attenuation = INVERSE_SQUARE;
+            Arc::new(point)
+        }
+        gltf::khr_lights_punctual::Kind::Spot { inner_cone_angle, outer_cone_angle } => {
+            let mut spot = SpotLight::new(position, forward, color, light.intensity(), inner_cone_angle, outer_cone_angle);
+            spot.attenuation = INVERSE_SQUARE;
+            Arc::new(spot)
+        }
+    }
+}
+
+// Carga la escena por defecto de un .gltf o .glb. `transform` se aplica sobre
+// la jerarquía de nodos del archivo. Sólo se admiten cámaras en perspectiva.
+pub fn load_gltf(path: &Path, transform: &Mat4) -> Result<GltfImport, ImportError> {
+    let gltf = gltf::Gltf::open(path).map_err(|err| ImportError::new(path, err.to_string()))?;
+
+    let mut buffers = Vec::new();
+    for buffer in gltf.document.buffers() {
+        let data = match buffer.source() {
+            gltf::buffer::Source::Bin => gltf.blob.clone()
+                .ok_or_else(|| ImportError::new(path, "missing binary chunk"))?,
+            gltf::buffer::Source::Uri(uri) => read_uri(path, uri)?,
+        };
+        if data.len() < buffer.length() {
+            return Err(ImportError::new(path, format!("buffer {} is shorter than declared", buffer.index())));
+        }
+        buffers.push(data);
+    }
+
+    let mut loader = Loader {
+        path,
+        buffers,
+        images: vec![None; gltf.document.images().len()],
+    };
+
+    let document = &gltf.document;
+    let mut materials = Vec::new();
+    for material in document.materials() {
+        materials.push(Arc::new(loader.material(material)?));
+    }
+    // Las primitivas sin material usan un gris mate
+    let default_material = Arc::new(Material::new(
+        Color::new(0.8, 0.8, 0.8),
+        1.0,
+        [1.0, 0.04, 0.0, 0.0],
+        DEFAULT_IOR,
+        None,
+        Color::black(),
+    ));
+
+    let mut import = GltfImport {
+        meshes: Vec::new(),
+        cameras: Vec::new(),
+        lights: Vec::new(),
+    };
+
+    let scene = document.default_scene().or_else(|| document.scenes().next())
+        .ok_or_else(|| ImportError::new(path, "file has no scenes"))?;
+    // Recorrido en preorden; los hijos se apilan al revés para visitarlos en orden
+    let mut pending: Vec<(gltf::Node, Mat4)> = scene.nodes().map(|node| (node, *transform)).collect();
+    pending.reverse();
+
+    while let Some((node, parent)) = pending.pop() {
+        let world = parent * Mat4::from(node.transform().matrix());
+
+        if let Some(mesh) = node.mesh() {
+            let triangles = loader.mesh(mesh, &world, &materials, &default_material);
+            if !triangles.is_empty() {
+                import.meshes.push(Mesh::new(triangles));
+            }
+        }
+
+        if let Some(camera) = node.camera() {
+            if let gltf::camera::Projection::Perspective(perspective) = camera.projection() {
+                let eye = transform_point(&world, &Vec3::zeros());
+                let forward = direction(&world, Vec3::new(0.0, 0.0, -1.0));
+                let mut camera = Camera::new(eye, eye + forward, direction(&world, Vec3::new(0.0, 1.0, 0.0)));
+                camera.fov = perspective.yfov();
+                import.cameras.push(camera);
+            }
+        }
+
+        if let Some(punctual) = node.light() {
+            import.lights.push(light(punctual, &world));
+        }
+
+        let first_child = pending.len();
+        pending.extend(node.children().map(|child| (child, world)));
+        pending[first_child..].reverse();
+    }
+
+    Ok(import)
+}
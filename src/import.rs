@@ -0,0 +1,47 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::texture::Texture;
+
+// Error al importar un archivo externo (modelo, estructura, mundo...)
+#[derive(Debug)]
+pub struct ImportError {
+    pub path: String,
+    pub message: String,
+}
+
+impl ImportError {
+    pub fn new(path: &Path, message: impl Into<String>) -> Self {
+        ImportError {
+            path: path.display().to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+// Carga cada imagen una sola vez aunque la usen varios materiales
+#[derive(Default)]
+pub struct TextureCache {
+    textures: HashMap<PathBuf, Arc<Texture>>,
+}
+
+impl TextureCache {
+    pub fn load(&mut self, path: &Path) -> Result<Arc<Texture>, ImportError> {
+        if let Some(texture) = self.textures.get(path) {
+            return Ok(Arc::clone(texture));
+        }
+        let texture = Texture::load(path)
+            .map_err(|err| ImportError::new(path, format!("could not load texture: {}", err)))?;
+        let texture = Arc::new(texture);
+        self.textures.insert(path.to_path_buf(), Arc::clone(&texture));
+        Ok(texture)
+    }
+}
@@ -0,0 +1,117 @@
+use nalgebra_glm::Vec3;
+
+use crate::color::Color;
+use crate::light::LightSample;
+use crate::ray_differential::RayDifferential;
+use crate::ray_intersect::{Intersect, RayIntersect};
+use crate::sampler::Sampler;
+use crate::scene::Scene;
+
+const ORIGIN_BIAS: f32 = 1e-4;
+
+// Estrategia de sombreado: calcula el color que llega a la cámara por un rayo.
+// El render sólo conoce este trait, así que se pueden añadir técnicas nuevas
+// sin tocar el bucle de la ventana. `differential` describe cómo varía el
+// rayo de un píxel al vecino y decide cuánto se filtran las texturas.
+pub trait Integrator: Send + Sync {
+    fn radiance(
+        &self,
+        scene: &Scene,
+        ray_origin: &Vec3,
+        ray_direction: &Vec3,
+        differential: &RayDifferential,
+        sampler: &mut Sampler,
+    ) -> Color;
+}
+
+pub fn offset_origin(intersect: &Intersect, direction: &Vec3) -> Vec3 {
+    let offset = intersect.normal * ORIGIN_BIAS;
+    if direction.dot(&intersect.normal) < 0.0 {
+        intersect.point - offset
+    } else {
+        intersect.point + offset
+    }
+}
+
+pub fn reflect(incident: &Vec3, normal: &Vec3) -> Vec3 {
+    incident - 2.0 * incident.dot(normal) * normal
+}
+
+pub fn refract(incident: &Vec3, normal: &Vec3, eta_t: f32) -> Vec3 {
+    let cosi = -incident.dot(normal).clamp(-1.0, 1.0);
+
+    let (n_cosi, eta, n_normal);
+
+    if cosi < 0.0 {
+        n_cosi = -cosi;
+        eta = 1.0 / eta_t;
+        n_normal = -normal;
+    } else {
+        n_cosi = cosi;
+        eta = eta_t;
+        n_normal = *normal;
+    }
+
+    let k = 1.0 - eta * eta * (1.0 - n_cosi * n_cosi);
+
+    if k < 0.0 {
+        reflect(incident, &n_normal)
+    } else {
+        eta * incident + (eta * n_cosi - k.sqrt()) * n_normal
+    }
+}
+
+// Devuelve 1.0 si algún objeto bloquea el camino hacia la muestra de la luz
+pub fn cast_shadow(
+    intersect: &Intersect,
+    light: &LightSample,
+    objects: &[Box<dyn RayIntersect>],
+) -> f32 {
+    let shadow_ray_origin = offset_origin(intersect, &light.direction);
+
+    for object in objects {
+        let shadow_intersect = object.ray_intersect(&shadow_ray_origin, &light.direction);
+        if shadow_intersect.is_intersecting && shadow_intersect.distance < light.distance {
+            return 1.0;
+        }
+    }
+
+    0.0
+}
+
+pub fn closest_intersect(ray_origin: &Vec3, ray_direction: &Vec3, objects: &[Box<dyn RayIntersect>]) -> Intersect {
+    let mut intersect = Intersect::empty();
+    let mut zbuffer = f32::INFINITY;
+
+    for object in objects {
+        let i = object.ray_intersect(ray_origin, ray_direction);
+        if i.is_intersecting && i.distance < zbuffer {
+            zbuffer = i.distance;
+            intersect = i;
+        }
+    }
+
+    intersect
+}
+
+// Color difuso del material en el punto de impacto, tomado de la textura si la
+// tiene. `footprint` es el ancho del píxel sobre la superficie.
+pub fn surface_color(intersect: &Intersect, footprint: f32) -> Color {
+    intersect.material.texture.as_ref().map_or(intersect.material.diffuse, |texture| {
+        texture.sample(intersect.uv, footprint * intersect.uv_density)
+    })
+}
+
+// Dirección con densidad proporcional a cos(θ) alrededor de `normal`
+pub fn cosine_hemisphere(normal: &Vec3, sampler: &mut Sampler) -> Vec3 {
+    let r = sampler.next_f32().sqrt();
+    let angle = 2.0 * std::f32::consts::PI * sampler.next_f32();
+    let (x, y) = (r * angle.cos(), r * angle.sin());
+    let z = (1.0 - x * x - y * y).max(0.0).sqrt();
+
+    let helper = if normal.x.abs() > 0.9 { Vec3::new(0.0, 1.0, 0.0) } else { Vec3::new(1.0, 0.0, 0.0) };
+    let tangent = normal.cross(&helper).normalize();
+    let bitangent = normal.cross(&tangent);
+
+    (tangent * x + bitangent * y + normal * z).normalize()
+}
@@ -0,0 +1,53 @@
+// Núcleo del trazador de rayos: geometría, materiales, luces, escenas,
+// integradores y render a un framebuffer. El visor de main.rs se apoya sólo en
+// lo que se exporta aquí.
+pub mod aabb;
+pub mod ambient_occlusion;
+pub mod anvil;
+pub mod bvh;
+pub mod camera;
+pub mod color;
+pub mod cube;
+pub mod debug_view;
+pub mod disk;
+pub mod filter;
+pub mod framebuffer;
+pub mod gltf_import;
+pub mod import;
+pub mod integrator;
+pub mod light;
+pub mod material;
+pub mod mesh;
+pub mod nbt;
+pub mod obj;
+pub mod path_tracer;
+pub mod plane;
+pub mod ray_differential;
+pub mod ray_intersect;
+pub mod rectangle;
+pub mod renderer;
+pub mod resource_pack;
+pub mod sampler;
+pub mod scene;
+pub mod scene_file;
+pub mod schematic;
+pub mod settings;
+pub mod sphere;
+pub mod square;
+pub mod texture;
+pub mod tonemap;
+pub mod triangle;
+pub mod vox;
+pub mod voxel_grid;
+pub mod whitted;
+
+pub use crate::camera::Camera;
+pub use crate::color::Color;
+pub use crate::framebuffer::Framebuffer;
+pub use crate::integrator::Integrator;
+pub use crate::material::Material;
+pub use crate::ray_intersect::{Intersect, RayIntersect};
+pub use crate::renderer::{render, render_progressive};
+pub use crate::scene::{Scene, SceneBuilder};
+pub use crate::settings::RenderSettings;
+pub use crate::tonemap::OutputTransform;
@@ -1,19 +1,320 @@
-
 use nalgebra_glm::Vec3;
 use crate::color::Color;
 
-pub struct Light {
+// Luz que llega a un punto desde una fuente: dirección hacia la fuente,
+// distancia hasta ella (infinita para luces direccionales) y radiancia.
+pub struct LightSample {
+    pub direction: Vec3,
+    pub distance: f32,
+    pub radiance: Color,
+}
+
+// `sample` es un punto en [0, 1)^2 que las luces de área usan para elegir un
+// punto de su superficie; las luces puntuales lo ignoran.
+pub trait LightSource: Send + Sync {
+    fn illuminate(&self, point: &Vec3, sample: (f32, f32)) -> LightSample;
+
+    // Rayos de sombra por punto sombreado
+    fn samples(&self) -> usize {
+        1
+    }
+
+    // Error si algún parámetro no tiene sentido, como una dirección nula
+    fn validate(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+// Permite compartir una misma luz entre varias listas (por ejemplo, la
+// iluminación de día y la de noche)
+impl<T: LightSource + ?Sized> LightSource for std::sync::Arc<T> {
+    fn illuminate(&self, point: &Vec3, sample: (f32, f32)) -> LightSample {
+        (**self).illuminate(point, sample)
+    }
+
+    fn samples(&self) -> usize {
+        (**self).samples()
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        (**self).validate()
+    }
+}
+
+// Coeficientes constante, lineal y cuadrático: 1 / (c + l·d + q·d²).
+// [1, 0, 0] no atenúa; [0, 0, 1] es la caída física con el cuadrado de la distancia.
+pub type Attenuation = [f32; 3];
+
+pub const NO_ATTENUATION: Attenuation = [1.0, 0.0, 0.0];
+
+fn is_finite(v: &Vec3) -> bool {
+    v.iter().all(|c| c.is_finite())
+}
+
+// Vector de longitud finita y no nula. Normalizar un vector nulo da NaN, que
+// tampoco pasa.
+fn is_direction(v: &Vec3) -> bool {
+    let length = v.magnitude();
+    length.is_finite() && length > 1e-12
+}
+
+// Color, intensidad y atenuación, comunes a todas las luces
+fn validate_emission(color: Color, intensity: f32, attenuation: &Attenuation) -> Result<(), String> {
+    if ![color.r, color.g, color.b].iter().all(|c| c.is_finite() && *c >= 0.0) {
+        return Err("color must be finite and non-negative".to_string());
+    }
+    if !(intensity.is_finite() && intensity >= 0.0) {
+        return Err(format!("intensity must be finite and non-negative, got {}", intensity));
+    }
+    if !attenuation.iter().all(|c| c.is_finite() && *c >= 0.0) {
+        return Err("attenuation coefficients must be finite and non-negative".to_string());
+    }
+    Ok(())
+}
+
+fn attenuate(attenuation: &Attenuation, distance: f32) -> f32 {
+    let [constant, linear, quadratic] = *attenuation;
+    1.0 / (constant + linear * distance + quadratic * distance * distance).max(1e-4)
+}
+
+pub struct PointLight {
     pub position: Vec3,
     pub color: Color,
     pub intensity: f32,
+    pub attenuation: Attenuation,
 }
 
-impl Light {
+impl PointLight {
     pub fn new(position: Vec3, color: Color, intensity: f32) -> Self {
-        Light {
+        PointLight {
             position,
             color,
             intensity,
+            attenuation: NO_ATTENUATION,
+        }
+    }
+}
+
+fn sample_towards(position: Vec3, point: &Vec3, radiance: Color, attenuation: &Attenuation) -> LightSample {
+    let to_light = position - point;
+    let distance = to_light.magnitude();
+    LightSample {
+        direction: to_light / distance,
+        distance,
+        radiance: radiance * attenuate(attenuation, distance),
+    }
+}
+
+impl LightSource for PointLight {
+    fn illuminate(&self, point: &Vec3, _sample: (f32, f32)) -> LightSample {
+        sample_towards(self.position, point, self.color * self.intensity, &self.attenuation)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if !is_finite(&self.position) {
+            return Err("position must be finite".to_string());
+        }
+        validate_emission(self.color, self.intensity, &self.attenuation)
+    }
+}
+
+// Luz muy lejana (el sol): todos los rayos son paralelos y no hay atenuación
+pub struct DirectionalLight {
+    pub direction: Vec3,
+    pub color: Color,
+    pub intensity: f32,
+}
+
+impl DirectionalLight {
+    pub fn new(direction: Vec3, color: Color, intensity: f32) -> Self {
+        DirectionalLight {
+            direction: direction.normalize(),
+            color,
+            intensity,
+        }
+    }
+}
+
+impl LightSource for DirectionalLight {
+    fn illuminate(&self, _point: &Vec3, _sample: (f32, f32)) -> LightSample {
+        LightSample {
+            direction: -self.direction,
+            distance: f32::INFINITY,
+            radiance: self.color * self.intensity,
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if !is_direction(&self.direction) {
+            return Err("direction must be non-zero and finite".to_string());
+        }
+        validate_emission(self.color, self.intensity, &NO_ATTENUATION)
+    }
+}
+
+// Luz puntual limitada a un cono. Entre los ángulos interior y exterior la
+// intensidad cae suavemente hasta cero.
+pub struct SpotLight {
+    pub position: Vec3,
+    pub direction: Vec3,
+    pub color: Color,
+    pub intensity: f32,
+    pub inner_angle: f32,
+    pub outer_angle: f32,
+    pub attenuation: Attenuation,
+}
+
+impl SpotLight {
+    pub fn new(position: Vec3, direction: Vec3, color: Color, intensity: f32, inner_angle: f32, outer_angle: f32) -> Self {
+        SpotLight {
+            position,
+            direction: direction.normalize(),
+            color,
+            intensity,
+            inner_angle,
+            outer_angle: outer_angle.max(inner_angle),
+            attenuation: NO_ATTENUATION,
+        }
+    }
+}
+
+impl LightSource for SpotLight {
+    fn illuminate(&self, point: &Vec3, _sample: (f32, f32)) -> LightSample {
+        let to_light = self.position - point;
+        let distance = to_light.magnitude();
+        let direction = to_light / distance;
+
+        let cos_angle = (-direction).dot(&self.direction);
+        let cos_inner = self.inner_angle.cos();
+        let cos_outer = self.outer_angle.cos();
+        let cone = if cos_inner - cos_outer > 1e-6 {
+            let t = ((cos_angle - cos_outer) / (cos_inner - cos_outer)).clamp(0.0, 1.0);
+            t * t * (3.0 - 2.0 * t)
+        } else if cos_angle >= cos_outer {
+            1.0
+        } else {
+            0.0
+        };
+
+        LightSample {
+            direction,
+            distance,
+            radiance: self.color * self.intensity * cone * attenuate(&self.attenuation, distance),
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if !is_finite(&self.position) {
+            return Err("position must be finite".to_string());
+        }
+        if !is_direction(&self.direction) {
+            return Err("direction must be non-zero and finite".to_string());
+        }
+        if !(self.inner_angle >= 0.0 && self.outer_angle <= std::f32::consts::PI) {
+            return Err("cone angles must be between 0 and 180 degrees".to_string());
+        }
+        validate_emission(self.color, self.intensity, &self.attenuation)
+    }
+}
+
+pub const DEFAULT_AREA_SAMPLES: usize = 16;
+
+// Rectángulo emisor con una esquina en `corner` y lados `edge_u` y `edge_v`.
+// Cada rayo de sombra apunta a un punto distinto del rectángulo, lo que produce
+// penumbras en lugar de sombras duras.
+pub struct RectLight {
+    pub corner: Vec3,
+    pub edge_u: Vec3,
+    pub edge_v: Vec3,
+    pub color: Color,
+    pub intensity: f32,
+    pub attenuation: Attenuation,
+    pub samples: usize,
+}
+
+impl RectLight {
+    pub fn new(corner: Vec3, edge_u: Vec3, edge_v: Vec3, color: Color, intensity: f32) -> Self {
+        RectLight {
+            corner,
+            edge_u,
+            edge_v,
+            color,
+            intensity,
+            attenuation: NO_ATTENUATION,
+            samples: DEFAULT_AREA_SAMPLES,
+        }
+    }
+}
+
+impl LightSource for RectLight {
+    fn illuminate(&self, point: &Vec3, sample: (f32, f32)) -> LightSample {
+        let position = self.corner + self.edge_u * sample.0 + self.edge_v * sample.1;
+        sample_towards(position, point, self.color * self.intensity, &self.attenuation)
+    }
+
+    fn samples(&self) -> usize {
+        self.samples.max(1)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if !is_finite(&self.corner) {
+            return Err("corner must be finite".to_string());
+        }
+        if !is_direction(&self.edge_u.cross(&self.edge_v)) {
+            return Err("edges must be non-zero, finite and not parallel".to_string());
+        }
+        validate_emission(self.color, self.intensity, &self.attenuation)
+    }
+}
+
+// Esfera emisora. Se muestrea el disco que la esfera presenta al punto sombreado.
+pub struct SphereLight {
+    pub center: Vec3,
+    pub radius: f32,
+    pub color: Color,
+    pub intensity: f32,
+    pub attenuation: Attenuation,
+    pub samples: usize,
+}
+
+impl SphereLight {
+    pub fn new(center: Vec3, radius: f32, color: Color, intensity: f32) -> Self {
+        SphereLight {
+            center,
+            radius,
+            color,
+            intensity,
+            attenuation: NO_ATTENUATION,
+            samples: DEFAULT_AREA_SAMPLES,
+        }
+    }
+}
+
+impl LightSource for SphereLight {
+    fn illuminate(&self, point: &Vec3, sample: (f32, f32)) -> LightSample {
+        let axis = (point - self.center).normalize();
+        let helper = if axis.x.abs() > 0.9 { Vec3::new(0.0, 1.0, 0.0) } else { Vec3::new(1.0, 0.0, 0.0) };
+        let tangent = axis.cross(&helper).normalize();
+        let bitangent = axis.cross(&tangent);
+
+        let radius = self.radius * sample.0.sqrt();
+        let angle = 2.0 * std::f32::consts::PI * sample.1;
+        let position = self.center + (tangent * angle.cos() + bitangent * angle.sin()) * radius;
+
+        sample_towards(position, point, self.color * self.intensity, &self.attenuation)
+    }
+
+    fn samples(&self) -> usize {
+        self.samples.max(1)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if !is_finite(&self.center) {
+            return Err("center must be finite".to_string());
+        }
+        if !(self.radius > 0.0 && self.radius.is_finite()) {
+            return Err(format!("radius must be positive and finite, got {}", self.radius));
         }
+        validate_emission(self.color, self.intensity, &self.attenuation)
     }
 }
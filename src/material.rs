@@ -1,7 +1,9 @@
 
+use nalgebra_glm::Vec3;
+
 use crate::color::Color;
 use crate::texture::Texture;
-use std::rc::Rc;
+use std::sync::Arc;
 
 #[derive(Debug, Clone)]
 pub struct Material {
@@ -9,7 +11,7 @@ pub struct Material {
     pub specular: f32,
     pub albedo: [f32; 4],
     pub refractive_index: f32,
-    pub texture: Option<Rc<Texture>>,
+    pub texture: Option<Arc<Texture>>,
     pub emissive_color: Color,
 }
 
@@ -19,7 +21,7 @@ impl Material {
         specular: f32,
         albedo: [f32; 4],
         refractive_index: f32,
-        texture: Option<Rc<Texture>>,
+        texture: Option<Arc<Texture>>,
         emissive_color: Color,
     ) -> Self {
         Material {
@@ -32,14 +34,61 @@ impl Material {
         }
     }
 
+    // Si la textura deja ver a través del material en `uv`
+    pub fn is_cutout(&self, uv: (f32, f32)) -> bool {
+        self.texture.as_ref().is_some_and(|texture| texture.is_cutout(uv))
+    }
+
     pub fn black() -> Self {
         Material {
-            diffuse: Color::new(0, 0, 0),
+            diffuse: Color::black(),
             specular: 0.0,
             albedo: [0.0, 0.0, 0.0, 0.0],
             refractive_index: 0.0,
             texture: None,
-            emissive_color: Color::new(0, 0, 0),
+            emissive_color: Color::black(),
+        }
+    }
+}
+
+// Materiales de las seis caras de un bloque. Los nombres siguen a Minecraft:
+// norte es -Z, sur +Z, este +X y oeste -X.
+#[derive(Debug, Clone)]
+pub struct BlockFaces {
+    pub top: Arc<Material>,
+    pub bottom: Arc<Material>,
+    pub north: Arc<Material>,
+    pub south: Arc<Material>,
+    pub east: Arc<Material>,
+    pub west: Arc<Material>,
+}
+
+impl BlockFaces {
+    pub fn uniform(material: Arc<Material>) -> Self {
+        BlockFaces {
+            top: Arc::clone(&material),
+            bottom: Arc::clone(&material),
+            north: Arc::clone(&material),
+            south: Arc::clone(&material),
+            east: Arc::clone(&material),
+            west: material,
+        }
+    }
+
+    // Material de la cara cuya normal (alineada con un eje) es `normal`
+    pub fn facing(&self, normal: &Vec3) -> &Arc<Material> {
+        if normal.y > 0.5 {
+            &self.top
+        } else if normal.y < -0.5 {
+            &self.bottom
+        } else if normal.x > 0.5 {
+            &self.east
+        } else if normal.x < -0.5 {
+            &self.west
+        } else if normal.z > 0.5 {
+            &self.south
+        } else {
+            &self.north
         }
     }
 }
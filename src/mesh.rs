@@ -0,0 +1,53 @@
+use nalgebra_glm::{Mat3, Mat4, Vec3, Vec4};
+
+use crate::aabb::Aabb;
+use crate::bvh::Bvh;
+use crate::ray_intersect::{Intersect, RayIntersect};
+use crate::triangle::Triangle;
+
+// Malla de triángulos con su propio BVH. Para el BVH de la escena es un solo
+// objeto, así que un modelo con miles de triángulos no desequilibra el resto.
+pub struct Mesh {
+    pub triangle_count: usize,
+    bvh: Bvh,
+}
+
+impl Mesh {
+    pub fn new(triangles: Vec<Triangle>) -> Self {
+        let triangle_count = triangles.len();
+        let objects = triangles
+            .into_iter()
+            .map(|triangle| Box::new(triangle) as Box<dyn RayIntersect>)
+            .collect();
+        Mesh {
+            triangle_count,
+            bvh: Bvh::new(objects),
+        }
+    }
+}
+
+impl RayIntersect for Mesh {
+    fn ray_intersect(&self, ray_origin: &Vec3, ray_direction: &Vec3) -> Intersect {
+        self.bvh.ray_intersect(ray_origin, ray_direction)
+    }
+
+    fn bounds(&self) -> Aabb {
+        self.bvh.bounds()
+    }
+}
+
+pub fn transform_point(transform: &Mat4, point: &Vec3) -> Vec3 {
+    let p = transform * Vec4::new(point.x, point.y, point.z, 1.0);
+    Vec3::new(p.x, p.y, p.z)
+}
+
+// Las normales se transforman con la inversa traspuesta para seguir siendo
+// perpendiculares con escalas no uniformes
+pub fn normal_matrix(transform: &Mat4) -> Mat3 {
+    let linear = Mat3::new(
+        transform[(0, 0)], transform[(0, 1)], transform[(0, 2)],
+        transform[(1, 0)], transform[(1, 1)], transform[(1, 2)],
+        transform[(2, 0)], transform[(2, 1)], transform[(2, 2)],
+    );
+    linear.try_inverse().map_or(linear, |inverse| inverse.transpose())
+}
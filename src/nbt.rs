@@ -0,0 +1,252 @@
+use flate2::read::{GzDecoder, ZlibDecoder};
+use std::collections::HashMap;
+use std::io::Read;
+
+// Límite de anidamiento para que un archivo malformado no agote la pila
+const MAX_DEPTH: usize = 512;
+
+// Valor NBT, el formato binario (big endian) de las estructuras, esquemas y
+// regiones de Minecraft
+#[derive(Debug, Clone, PartialEq)]
+pub enum Tag {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray(Vec<i8>),
+    String(String),
+    List(Vec<Tag>),
+    Compound(HashMap<String, Tag>),
+    IntArray(Vec<i32>),
+    LongArray(Vec<i64>),
+}
+
+impl Tag {
+    pub fn get(&self, key: &str) -> Option<&Tag> {
+        match self {
+            Tag::Compound(entries) => entries.get(key),
+            _ => None,
+        }
+    }
+
+    // Cualquier entero como i64; los esquemas no siempre usan el mismo ancho
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            Tag::Byte(v) => Some(v as i64),
+            Tag::Short(v) => Some(v as i64),
+            Tag::Int(v) => Some(v as i64),
+            Tag::Long(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Tag::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[Tag]> {
+        match self {
+            Tag::List(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn as_compound(&self) -> Option<&HashMap<String, Tag>> {
+        match self {
+            Tag::Compound(entries) => Some(entries),
+            _ => None,
+        }
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, count: usize) -> Result<&'a [u8], String> {
+        let end = self.position.checked_add(count).filter(|&end| end <= self.data.len())
+            .ok_or_else(|| "truncated NBT data".to_string())?;
+        let bytes = &self.data[self.position..end];
+        self.position = end;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.bytes(N)?);
+        Ok(array)
+    }
+
+    fn length(&mut self) -> Result<usize, String> {
+        let length = i32::from_be_bytes(self.array()?);
+        // Una longitud imposible delata un archivo roto antes de reservar memoria
+        usize::try_from(length).ok().filter(|&len| len <= self.data.len() - self.position)
+            .ok_or_else(|| format!("invalid NBT length {}", length))
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        let length = u16::from_be_bytes(self.array()?) as usize;
+        // Java usa UTF-8 modificado; basta para los nombres de bloques
+        Ok(String::from_utf8_lossy(self.bytes(length)?).into_owned())
+    }
+
+    fn payload(&mut self, id: u8, depth: usize) -> Result<Tag, String> {
+        if depth > MAX_DEPTH {
+            return Err("NBT data is nested too deeply".to_string());
+        }
+        Ok(match id {
+            1 => Tag::Byte(i8::from_be_bytes(self.array()?)),
+            2 => Tag::Short(i16::from_be_bytes(self.array()?)),
+            3 => Tag::Int(i32::from_be_bytes(self.array()?)),
+            4 => Tag::Long(i64::from_be_bytes(self.array()?)),
+            5 => Tag::Float(f32::from_be_bytes(self.array()?)),
+            6 => Tag::Double(f64::from_be_bytes(self.array()?)),
+            7 => {
+                let length = self.length()?;
+                Tag::ByteArray(self.bytes(length)?.iter().map(|&b| b as i8).collect())
+            }
+            8 => Tag::String(self.string()?),
+            9 => {
+                let item = self.array::<1>()?[0];
+                let length = self.length()?;
+                let mut items = Vec::with_capacity(length);
+                for _ in 0..length {
+                    items.push(self.payload(item, depth + 1)?);
+                }
+                Tag::List(items)
+            }
+            10 => {
+                let mut entries = HashMap::new();
+                loop {
+                    let id = self.array::<1>()?[0];
+                    if id == 0 {
+                        break;
+                    }
+                    let name = self.string()?;
+                    entries.insert(name, self.payload(id, depth + 1)?);
+                }
+                Tag::Compound(entries)
+            }
+            11 => {
+                let length = self.length()?;
+                let mut values = Vec::with_capacity(length);
+                for _ in 0..length {
+                    values.push(i32::from_be_bytes(self.array()?));
+                }
+                Tag::IntArray(values)
+            }
+            12 => {
+                let length = self.length()?;
+                let mut values = Vec::with_capacity(length);
+                for _ in 0..length {
+                    values.push(i64::from_be_bytes(self.array()?));
+                }
+                Tag::LongArray(values)
+            }
+            _ => return Err(format!("unknown NBT tag {}", id)),
+        })
+    }
+}
+
+// Lee el compuesto raíz de unos datos NBT sin comprimir
+pub fn parse(data: &[u8]) -> Result<Tag, String> {
+    let mut reader = Reader { data, position: 0 };
+    let id = reader.array::<1>()?[0];
+    if id != 10 {
+        return Err("NBT data does not start with a compound".to_string());
+    }
+    reader.string()?;
+    reader.payload(id, 0)
+}
+
+// Datos NBT comprimidos con gzip (estructuras y esquemas) o zlib (regiones);
+// los datos sin comprimir se leen tal cual
+pub fn decompress(data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut decompressed = Vec::new();
+    let result = match data {
+        [0x1f, 0x8b, ..] => GzDecoder::new(data).read_to_end(&mut decompressed),
+        [0x78, ..] => ZlibDecoder::new(data).read_to_end(&mut decompressed),
+        _ => return Ok(data.to_vec()),
+    };
+    result.map_err(|err| format!("could not decompress NBT data: {}", err))?;
+    Ok(decompressed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    // Compuesto raíz sin nombre con un valor de cada tipo usado por los
+    // importadores
+    const ALL_TAGS: &[u8] = &[
+        0x0a, 0x00, 0x00,
+        0x01, 0x00, 0x01, b'b', 0xfe,
+        0x02, 0x00, 0x01, b's', 0x01, 0x2c,
+        0x03, 0x00, 0x01, b'i', 0xff, 0xff, 0xff, 0xff,
+        0x04, 0x00, 0x01, b'l', 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x08, 0x00, 0x01, b'n', 0x00, 0x05, b's', b't', b'o', b'n', b'e',
+        0x07, 0x00, 0x01, b'y', 0x00, 0x00, 0x00, 0x02, 0x05, 0x81,
+        0x09, 0x00, 0x01, b'p', 0x03, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x02,
+        0x0a, 0x00, 0x01, b'c', 0x03, 0x00, 0x01, b'x', 0x00, 0x00, 0x00, 0x07, 0x00,
+        0x0b, 0x00, 0x01, b'a', 0x00, 0x00, 0x00, 0x01, 0x80, 0x00, 0x00, 0x00,
+        0x0c, 0x00, 0x01, b'g', 0x00, 0x00, 0x00, 0x01, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe,
+        0x00,
+    ];
+
+    #[test]
+    fn parses_every_tag_type() {
+        let root = parse(ALL_TAGS).unwrap();
+        assert_eq!(root.get("b"), Some(&Tag::Byte(-2)));
+        assert_eq!(root.get("s").and_then(Tag::as_i64), Some(300));
+        assert_eq!(root.get("i").and_then(Tag::as_i64), Some(-1));
+        assert_eq!(root.get("l").and_then(Tag::as_i64), Some(1 << 40));
+        assert_eq!(root.get("n").and_then(Tag::as_str), Some("stone"));
+        assert_eq!(root.get("y"), Some(&Tag::ByteArray(vec![5, -127])));
+        assert_eq!(root.get("p").and_then(Tag::as_list), Some([Tag::Int(1), Tag::Int(2)].as_slice()));
+        assert_eq!(root.get("c").and_then(|c| c.get("x")).and_then(Tag::as_i64), Some(7));
+        assert_eq!(root.get("a"), Some(&Tag::IntArray(vec![i32::MIN])));
+        assert_eq!(root.get("g"), Some(&Tag::LongArray(vec![-2])));
+    }
+
+    #[test]
+    fn rejects_malformed_data() {
+        assert!(parse(&ALL_TAGS[..ALL_TAGS.len() - 3]).is_err());
+        assert!(parse(&[0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01]).is_err());
+        assert!(parse(&[0x0a, 0x00, 0x00, 0x0d, 0x00, 0x00, 0x00]).is_err());
+    }
+
+    #[test]
+    fn rejects_deep_nesting() {
+        // Listas de una lista anidadas `depth` veces
+        let nested = |depth: usize| {
+            let mut data = vec![0x0a, 0x00, 0x00, 0x09, 0x00, 0x01, b'l'];
+            for _ in 0..depth {
+                data.extend_from_slice(&[0x09, 0x00, 0x00, 0x00, 0x01]);
+            }
+            data.extend_from_slice(&[0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+            data
+        };
+        assert!(parse(&nested(8)).is_ok());
+        assert!(parse(&nested(MAX_DEPTH + 8)).is_err());
+    }
+
+    #[test]
+    fn decompresses_gzip_and_passes_raw_data() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(ALL_TAGS).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        assert_eq!(decompress(&compressed).unwrap(), ALL_TAGS);
+        assert_eq!(decompress(ALL_TAGS).unwrap(), ALL_TAGS);
+    }
+}
@@ -0,0 +1,109 @@
+use nalgebra_glm::{Mat4, Vec3};
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::color::Color;
+use crate::import::{ImportError, TextureCache};
+use crate::material::Material;
+use crate::mesh::{normal_matrix, transform_point, Mesh};
+use crate::triangle::Triangle;
+
+// Traduce un material MTL al modelo de la escena: Kd es el color difuso, Ns el
+// exponente especular, la disolución `d` da la transparencia e `illum` 3 a 7
+// activa el reflejo con la intensidad de Ks. Las rutas de textura son
+// relativas al archivo OBJ.
+fn material(mtl: &tobj::Material, base_dir: &Path, textures: &mut TextureCache) -> Result<Material, ImportError> {
+    let [r, g, b] = mtl.diffuse.unwrap_or([0.8, 0.8, 0.8]);
+    let specular = mtl.specular.unwrap_or([0.0, 0.0, 0.0]);
+    let specular_weight = specular[0].max(specular[1]).max(specular[2]);
+
+    let reflectivity = match mtl.illumination_model {
+        Some(3..=7) => specular_weight,
+        _ => 0.0,
+    };
+    let transparency = (1.0 - mtl.dissolve.unwrap_or(1.0)).clamp(0.0, 1.0 - reflectivity);
+    let emissive = mtl.unknown_param.get("Ke").and_then(|value| {
+        let channels: Vec<f32> = value.split_whitespace().filter_map(|c| c.parse().ok()).collect();
+        match channels[..] {
+            [r, g, b] => Some(Color::new(r, g, b)),
+            _ => None,
+        }
+    });
+
+    let texture = match &mtl.diffuse_texture {
+        Some(name) => Some(textures.load(&base_dir.join(name))?),
+        None => None,
+    };
+
+    Ok(Material::new(
+        Color::new(r, g, b),
+        mtl.shininess.unwrap_or(0.0),
+        [1.0, specular_weight, reflectivity, transparency],
+        mtl.optical_density.unwrap_or(1.0),
+        texture,
+        emissive.unwrap_or(Color::black()),
+    ))
+}
+
+// Carga un OBJ como una sola malla. `transform` lleva los vértices del archivo
+// a la posición que ocupan en la escena.
+pub fn load_obj(path: &Path, transform: &Mat4) -> Result<Mesh, ImportError> {
+    let (models, materials) = tobj::load_obj(path, &tobj::GPU_LOAD_OPTIONS)
+        .map_err(|err| ImportError::new(path, err.to_string()))?;
+    let base_dir = path.parent().unwrap_or(Path::new("."));
+
+    // Un OBJ sin MTL (o con un MTL que no se encuentra) usa un gris por defecto
+    let mut textures = TextureCache::default();
+    let materials = materials
+        .unwrap_or_default()
+        .iter()
+        .map(|mtl| material(mtl, base_dir, &mut textures).map(Arc::new))
+        .collect::<Result<Vec<_>, _>>()?;
+    let default_material = Arc::new(Material::new(
+        Color::new(0.8, 0.8, 0.8),
+        0.0,
+        [1.0, 0.0, 0.0, 0.0],
+        1.0,
+        None,
+        Color::black(),
+    ));
+
+    let normals_to_world = normal_matrix(transform);
+    let mut triangles = Vec::new();
+
+    for model in &models {
+        let mesh = &model.mesh;
+        let material = mesh.material_id
+            .and_then(|id| materials.get(id))
+            .unwrap_or(&default_material);
+
+        let position = |i: usize| {
+            let p = Vec3::new(mesh.positions[3 * i], mesh.positions[3 * i + 1], mesh.positions[3 * i + 2]);
+            transform_point(transform, &p)
+        };
+        let normal = |i: usize| {
+            let n = Vec3::new(mesh.normals[3 * i], mesh.normals[3 * i + 1], mesh.normals[3 * i + 2]);
+            (normals_to_world * n).normalize()
+        };
+        // En OBJ la v crece hacia arriba; en las imágenes, hacia abajo
+        let uv = |i: usize| (mesh.texcoords[2 * i], 1.0 - mesh.texcoords[2 * i + 1]);
+
+        for face in mesh.indices.chunks_exact(3) {
+            let [a, b, c] = [face[0] as usize, face[1] as usize, face[2] as usize];
+            let mut triangle = Triangle::new(position(a), position(b), position(c), Arc::clone(material));
+            if !mesh.normals.is_empty() {
+                triangle = triangle.with_normals([normal(a), normal(b), normal(c)]);
+            }
+            if !mesh.texcoords.is_empty() {
+                triangle = triangle.with_uvs([uv(a), uv(b), uv(c)]);
+            }
+            triangles.push(triangle);
+        }
+    }
+
+    if triangles.is_empty() {
+        return Err(ImportError::new(path, "model has no triangles"));
+    }
+
+    Ok(Mesh::new(triangles))
+}
@@ -0,0 +1,106 @@
+use nalgebra_glm::Vec3;
+use std::f32::consts::PI;
+
+use crate::color::Color;
+use crate::integrator::{
+    cast_shadow, closest_intersect, cosine_hemisphere, offset_origin, reflect, refract, surface_color, Integrator,
+};
+use crate::ray_differential::RayDifferential;
+use crate::sampler::Sampler;
+use crate::scene::Scene;
+
+pub const DEFAULT_MAX_BOUNCES: u32 = 16;
+// Rebotes antes de empezar a cortar caminos con ruleta rusa
+const ROULETTE_DEPTH: u32 = 3;
+
+fn max_channel(color: Color) -> f32 {
+    color.r.max(color.g).max(color.b)
+}
+
+// Estima la radiancia que llega por un rayo siguiendo un camino aleatorio.
+// En cada impacto se elige un lóbulo del material (difuso, espejo o
+// refracción) según su albedo. En los rebotes difusos se muestrean además las
+// luces de la escena (estimación del siguiente evento); los materiales
+// emisivos y el cielo aportan luz cuando un camino los encuentra.
+pub struct PathTracer {
+    pub max_bounces: u32,
+}
+
+impl PathTracer {
+    pub fn new(max_bounces: u32) -> Self {
+        PathTracer { max_bounces }
+    }
+}
+
+impl Integrator for PathTracer {
+    fn radiance(
+        &self,
+        scene: &Scene,
+        ray_origin: &Vec3,
+        ray_direction: &Vec3,
+        differential: &RayDifferential,
+        sampler: &mut Sampler,
+    ) -> Color {
+        let mut radiance = Color::black();
+        let mut throughput = Color::new(1.0, 1.0, 1.0);
+        let mut origin = *ray_origin;
+        let mut direction = *ray_direction;
+        let mut differential = *differential;
+
+        for depth in 0..self.max_bounces {
+            let intersect = closest_intersect(&origin, &direction, &scene.objects);
+            if !intersect.is_intersecting {
+                radiance = radiance + throughput * scene.lighting().sky_color;
+                break;
+            }
+
+            let material = &intersect.material;
+            radiance = radiance + throughput * material.emissive_color;
+
+            let reflectivity = material.albedo[2].clamp(0.0, 1.0);
+            let transparency = material.albedo[3].clamp(0.0, 1.0 - reflectivity);
+            let choice = sampler.next_f32();
+
+            if choice < reflectivity {
+                differential = differential.reflect(&direction, &intersect);
+                direction = reflect(&direction, &intersect.normal).normalize();
+            } else if choice < reflectivity + transparency {
+                differential = differential.transmit(&direction, &intersect);
+                direction = refract(&direction, &intersect.normal, material.refractive_index).normalize();
+            } else {
+                // La elección del lóbulo ya pondera por (1 - reflejo - transparencia)
+                let footprint = differential.footprint(&direction, &intersect);
+                let base = surface_color(&intersect, footprint) * material.albedo[0].clamp(0.0, 1.0);
+                let normal = if intersect.normal.dot(&direction) > 0.0 { -intersect.normal } else { intersect.normal };
+
+                for light in &scene.lighting().lights {
+                    let sample = light.illuminate(&intersect.point, (sampler.next_f32(), sampler.next_f32()));
+                    let cos_theta = normal.dot(&sample.direction);
+                    if cos_theta <= 0.0 || cast_shadow(&intersect, &sample, &scene.objects) > 0.0 {
+                        continue;
+                    }
+                    // BRDF lambertiana: base / π. En el rebote indirecto el muestreo
+                    // por coseno cancela ese π; aquí hay que aplicarlo.
+                    radiance = radiance + throughput * base * sample.radiance * (cos_theta / PI);
+                }
+
+                throughput = throughput * base;
+                // Tras un rebote difuso el detalle de las texturas apenas se
+                // nota; basta con que la huella parta del punto de impacto
+                differential = differential.transmit(&direction, &intersect);
+                direction = cosine_hemisphere(&normal, sampler);
+            }
+            origin = offset_origin(&intersect, &direction);
+
+            if depth >= ROULETTE_DEPTH {
+                let survival = max_channel(throughput).clamp(0.05, 0.95);
+                if sampler.next_f32() > survival {
+                    break;
+                }
+                throughput = throughput * (1.0 / survival);
+            }
+        }
+
+        radiance
+    }
+}
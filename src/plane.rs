@@ -0,0 +1,69 @@
+use nalgebra_glm::Vec3;
+use std::sync::Arc;
+use crate::aabb::Aabb;
+use crate::material::Material;
+use crate::ray_intersect::{RayIntersect, Intersect};
+
+// Dos ejes perpendiculares a `normal` y entre sí
+pub fn tangent_frame(normal: &Vec3) -> (Vec3, Vec3) {
+    let helper = if normal.y.abs() > 0.9 { Vec3::new(1.0, 0.0, 0.0) } else { Vec3::new(0.0, 1.0, 0.0) };
+    let tangent = helper.cross(normal).normalize();
+    let bitangent = normal.cross(&tangent);
+    (tangent, bitangent)
+}
+
+// Distancia a la que el rayo corta el plano que pasa por `point`, si lo corta
+// por delante del origen
+pub fn plane_distance(point: &Vec3, normal: &Vec3, ray_origin: &Vec3, ray_direction: &Vec3) -> Option<f32> {
+    let denom = normal.dot(ray_direction);
+    if denom.abs() < 1e-6 {
+        return None;
+    }
+    let t = (point - ray_origin).dot(normal) / denom;
+    if t >= 0.0 { Some(t) } else { None }
+}
+
+// Plano infinito con cualquier orientación. La textura se repite cada unidad.
+pub struct Plane {
+    pub point: Vec3,
+    pub normal: Vec3,
+    pub material: Arc<Material>,
+}
+
+impl Plane {
+    pub fn new(point: Vec3, normal: Vec3, material: Arc<Material>) -> Self {
+        Plane {
+            point,
+            normal: normal.normalize(),
+            material,
+        }
+    }
+}
+
+impl RayIntersect for Plane {
+    fn ray_intersect(&self, ray_origin: &Vec3, ray_direction: &Vec3) -> Intersect {
+        let Some(t) = plane_distance(&self.point, &self.normal, ray_origin, ray_direction) else {
+            return Intersect::empty();
+        };
+
+        let point = ray_origin + ray_direction * t;
+        let (tangent, bitangent) = tangent_frame(&self.normal);
+        let local = point - self.point;
+        let uv = (local.dot(&tangent).rem_euclid(1.0), (-local.dot(&bitangent)).rem_euclid(1.0));
+
+        Intersect::new(point, self.normal, t, Arc::clone(&self.material), uv).with_uv_density(1.0)
+    }
+
+    fn bounds(&self) -> Aabb {
+        Aabb::infinite()
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        // `new` normaliza la normal, así que una normal nula queda en NaN
+        let length = self.normal.magnitude();
+        if !(length > 0.0 && length.is_finite()) {
+            return Err("plane normal must be non-zero and finite".to_string());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+use nalgebra_glm::Vec3;
+
+use crate::ray_intersect::Intersect;
+
+// Diferenciales de un rayo (Igehy, "Tracing Ray Differentials"): cuánto
+// cambian su origen y su dirección al pasar al píxel vecino en x y en y. Sirven
+// para saber qué tamaño tiene el píxel allí donde el rayo toca una superficie.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RayDifferential {
+    pub origin_dx: Vec3,
+    pub origin_dy: Vec3,
+    pub direction_dx: Vec3,
+    pub direction_dy: Vec3,
+}
+
+impl RayDifferential {
+    // Rayos que salen todos del mismo punto, como los de la cámara
+    pub fn from_directions(direction_dx: Vec3, direction_dy: Vec3) -> Self {
+        RayDifferential {
+            origin_dx: Vec3::zeros(),
+            origin_dy: Vec3::zeros(),
+            direction_dx,
+            direction_dy,
+        }
+    }
+
+    // Diferenciales del punto de impacto, sobre el plano tangente
+    fn transfer(&self, direction: &Vec3, intersect: &Intersect) -> (Vec3, Vec3) {
+        let normal = intersect.normal;
+        let denom = direction.dot(&normal);
+        if denom.abs() < 1e-6 {
+            // Rayo rasante: la huella del píxel no tiene límite
+            return (Vec3::repeat(f32::INFINITY), Vec3::repeat(f32::INFINITY));
+        }
+
+        let transfer = |origin: Vec3, delta: Vec3| {
+            let offset = origin + delta * intersect.distance;
+            offset - direction * (offset.dot(&normal) / denom)
+        };
+        (transfer(self.origin_dx, self.direction_dx), transfer(self.origin_dy, self.direction_dy))
+    }
+
+    // Ancho del píxel sobre la superficie en el punto de impacto
+    pub fn footprint(&self, direction: &Vec3, intersect: &Intersect) -> f32 {
+        let (dx, dy) = self.transfer(direction, intersect);
+        dx.norm().max(dy.norm())
+    }
+
+    // Diferenciales del rayo reflejado, tratando la superficie como plana
+    pub fn reflect(&self, direction: &Vec3, intersect: &Intersect) -> Self {
+        let (origin_dx, origin_dy) = self.transfer(direction, intersect);
+        let normal = intersect.normal;
+        let mirror = |delta: Vec3| delta - normal * (2.0 * delta.dot(&normal));
+        RayDifferential {
+            origin_dx,
+            origin_dy,
+            direction_dx: mirror(self.direction_dx),
+            direction_dy: mirror(self.direction_dy),
+        }
+    }
+
+    // Diferenciales del rayo que atraviesa la superficie. La refracción real
+    // las ensancha o estrecha; aquí se conserva el cambio de dirección.
+    pub fn transmit(&self, direction: &Vec3, intersect: &Intersect) -> Self {
+        let (origin_dx, origin_dy) = self.transfer(direction, intersect);
+        RayDifferential { origin_dx, origin_dy, ..*self }
+    }
+}
@@ -1,40 +1,78 @@
 
 use nalgebra_glm::Vec3;
+use crate::aabb::Aabb;
 use crate::material::Material;
-use std::rc::Rc; // Asegúrate de importar Rc
+use std::sync::Arc;
 
 #[derive(Debug, Clone)]
-#[allow(dead_code)]
 pub struct Intersect {
     pub point: Vec3,
     pub normal: Vec3,
     pub distance: f32,
     pub is_intersecting: bool,
-    pub material: Rc<Material>, // Mantener esto como Rc<Material>
+    // Coordenadas de textura relativas al objeto que calcula el impacto
+    pub uv: (f32, f32),
+    // Unidades de UV por unidad de distancia en la superficie, para pasar el
+    // tamaño del píxel a texeles al elegir el mip
+    pub uv_density: f32,
+    pub material: Arc<Material>,
 }
 
 impl Intersect {
-    pub fn new(point: Vec3, normal: Vec3, distance: f32, material: Rc<Material>) -> Self {
+    pub fn new(point: Vec3, normal: Vec3, distance: f32, material: Arc<Material>, uv: (f32, f32)) -> Self {
         Intersect {
             point,
             normal,
             distance,
             is_intersecting: true,
+            uv,
+            uv_density: 0.0,
             material,
         }
     }
 
+    pub fn with_uv_density(mut self, density: f32) -> Self {
+        self.uv_density = density;
+        self
+    }
+
     pub fn empty() -> Self {
         Intersect {
             point: Vec3::zeros(),
             normal: Vec3::zeros(),
             distance: 0.0,
             is_intersecting: false,
-            material: Rc::new(Material::black()), // Cambiar a Rc<Material>
+            uv: (0.0, 0.0),
+            uv_density: 0.0,
+            material: Arc::new(Material::black()),
         }
     }
 }
 
-pub trait RayIntersect {
+pub trait RayIntersect: Send + Sync {
     fn ray_intersect(&self, ray_origin: &Vec3, ray_direction: &Vec3) -> Intersect;
-}
\ No newline at end of file
+
+    // Caja envolvente en espacio de mundo, usada por el BVH
+    fn bounds(&self) -> Aabb;
+
+    // Error si algún parámetro no tiene sentido, como un radio nulo
+    fn validate(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+// Permite compartir un objeto entre la escena y quien lo actualiza, como los
+// chunks de un mundo que se cargan a medida que se mueve la cámara
+impl<T: RayIntersect + ?Sized> RayIntersect for Arc<T> {
+    fn ray_intersect(&self, ray_origin: &Vec3, ray_direction: &Vec3) -> Intersect {
+        (**self).ray_intersect(ray_origin, ray_direction)
+    }
+
+    fn bounds(&self) -> Aabb {
+        (**self).bounds()
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        (**self).validate()
+    }
+}
@@ -0,0 +1,66 @@
+use nalgebra_glm::Vec3;
+use std::sync::Arc;
+use crate::aabb::Aabb;
+use crate::material::Material;
+use crate::plane::plane_distance;
+use crate::ray_intersect::{RayIntersect, Intersect};
+
+// Paralelogramo con una esquina en `corner` y lados `edge_u` y `edge_v`. La
+// textura va de izquierda a derecha a lo largo de `edge_u` y de abajo arriba a
+// lo largo de `edge_v`; la normal es edge_u × edge_v.
+pub struct Rectangle {
+    pub corner: Vec3,
+    pub edge_u: Vec3,
+    pub edge_v: Vec3,
+    pub material: Arc<Material>,
+}
+
+impl Rectangle {
+    pub fn new(corner: Vec3, edge_u: Vec3, edge_v: Vec3, material: Arc<Material>) -> Self {
+        Rectangle { corner, edge_u, edge_v, material }
+    }
+}
+
+impl RayIntersect for Rectangle {
+    fn ray_intersect(&self, ray_origin: &Vec3, ray_direction: &Vec3) -> Intersect {
+        let n = self.edge_u.cross(&self.edge_v);
+        let area = n.dot(&n);
+        if area < 1e-12 {
+            return Intersect::empty();
+        }
+        let Some(t) = plane_distance(&self.corner, &n, ray_origin, ray_direction) else {
+            return Intersect::empty();
+        };
+
+        // Coordenadas del impacto en la base (edge_u, edge_v)
+        let point = ray_origin + ray_direction * t;
+        let local = point - self.corner;
+        let a = n.dot(&local.cross(&self.edge_v)) / area;
+        let b = n.dot(&self.edge_u.cross(&local)) / area;
+        if !(0.0..=1.0).contains(&a) || !(0.0..=1.0).contains(&b) {
+            return Intersect::empty();
+        }
+
+        Intersect::new(point, n.normalize(), t, Arc::clone(&self.material), (a, 1.0 - b)).with_uv_density(1.0 / n.norm().sqrt())
+    }
+
+    fn bounds(&self) -> Aabb {
+        let corners = [
+            self.corner,
+            self.corner + self.edge_u,
+            self.corner + self.edge_v,
+            self.corner + self.edge_u + self.edge_v,
+        ];
+        let min = corners.iter().fold(corners[0], |min, c| min.inf(c));
+        let max = corners.iter().fold(corners[0], |max, c| max.sup(c));
+        Aabb::new(min, max)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let area = self.edge_u.cross(&self.edge_v).magnitude();
+        if !(area > 0.0 && area.is_finite()) {
+            return Err("rectangle edges must be non-zero, finite and not parallel".to_string());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,195 @@
+use nalgebra_glm::{Vec3, normalize};
+use std::sync::Mutex;
+use std::thread;
+
+use crate::camera::Camera;
+use crate::filter::{FilterSum, FilterTable};
+use crate::framebuffer::Framebuffer;
+use crate::integrator::Integrator;
+use crate::ray_differential::RayDifferential;
+use crate::sampler::Sampler;
+use crate::scene::Scene;
+use crate::settings::RenderSettings;
+
+// Devuelve una función con la dirección del rayo que pasa por el punto
+// (px, py) de la pantalla, medido en píxeles
+fn primary_rays(camera: &Camera, width: usize, height: usize) -> impl Fn(f32, f32) -> Vec3 + Sync + '_ {
+    let width = width as f32;
+    let height = height as f32;
+    let aspect_ratio = width / height;
+    let fov = camera.fov;
+    let perspective_scale = (fov * 0.5).tan();
+
+    move |px: f32, py: f32| {
+        let screen_x = (2.0 * px) / width - 1.0;
+        let screen_y = -(2.0 * py) / height + 1.0;
+
+        let screen_x = screen_x * aspect_ratio * perspective_scale;
+        let screen_y = screen_y * perspective_scale;
+
+        let ray_direction = normalize(&Vec3::new(screen_x, screen_y, -1.0));
+
+        camera.base_change(&ray_direction)
+    }
+}
+
+// Diferenciales del rayo primario que pasa por (px, py): la diferencia con los
+// rayos que pasan un píxel a la derecha y uno abajo
+fn primary_differential(primary_ray: &impl Fn(f32, f32) -> Vec3, px: f32, py: f32, direction: &Vec3) -> RayDifferential {
+    RayDifferential::from_directions(primary_ray(px + 1.0, py) - direction, primary_ray(px, py + 1.0) - direction)
+}
+
+// Cada hilo toma la siguiente fila libre; cada píxel se calcula igual que en
+// un solo hilo, así que la imagen no depende del número de hilos.
+fn for_each_row<T: Send>(rows: impl Iterator<Item = T> + Send, threads: usize, render_row: impl Fn(T) + Sync) {
+    let rows = Mutex::new(rows);
+
+    let render_rows = || loop {
+        let Some(row) = rows.lock().unwrap().next() else {
+            break;
+        };
+        render_row(row);
+    };
+
+    thread::scope(|scope| {
+        for _ in 1..threads {
+            scope.spawn(render_rows);
+        }
+        render_rows();
+    });
+}
+
+pub fn render(
+    framebuffer: &mut Framebuffer,
+    scene: &Scene,
+    integrator: &dyn Integrator,
+    settings: &RenderSettings,
+) {
+    let samples = settings.samples_per_pixel.max(1);
+    let filter = FilterTable::new(settings.filter);
+    let primary_ray = primary_rays(&scene.camera, framebuffer.width, framebuffer.height);
+    let trace = |px: f32, py: f32, sampler: &mut Sampler| {
+        let direction = primary_ray(px, py);
+        let differential = primary_differential(&primary_ray, px, py, &direction);
+        integrator.radiance(scene, &scene.camera.eye, &direction, &differential, sampler)
+    };
+    let rows = framebuffer.radiance.chunks_mut(framebuffer.width).enumerate();
+
+    for_each_row(rows, settings.thread_count(), |(y, row)| {
+        for (x, pixel) in row.iter_mut().enumerate() {
+            let mut sampler = Sampler::for_pixel(x, y, 0);
+            let center_x = x as f32 + 0.5;
+            let center_y = y as f32 + 0.5;
+
+            if samples == 1 {
+                *pixel = trace(center_x, center_y, &mut sampler);
+                continue;
+            }
+
+            // Muestras estratificadas sobre el soporte del filtro
+            let mut sum = FilterSum::new();
+            for index in 0..samples {
+                let (dx, dy, positive) = filter.sample(sampler.stratified(index, samples));
+                sum.add(trace(center_x + dx, center_y + dy, &mut sampler), positive);
+            }
+
+            *pixel = sum.resolve(&filter);
+        }
+    });
+}
+
+// Añade una muestra con desplazamiento aleatorio por píxel al acumulador del
+// framebuffer y deja en `radiance` el promedio de todas las muestras hasta ahora.
+pub fn render_progressive(
+    framebuffer: &mut Framebuffer,
+    scene: &Scene,
+    integrator: &dyn Integrator,
+    settings: &RenderSettings,
+) {
+    let frame = framebuffer.accumulated_frames;
+    let filter = FilterTable::new(settings.filter);
+    let primary_ray = primary_rays(&scene.camera, framebuffer.width, framebuffer.height);
+    let trace = |px: f32, py: f32, sampler: &mut Sampler| {
+        let direction = primary_ray(px, py);
+        let differential = primary_differential(&primary_ray, px, py, &direction);
+        integrator.radiance(scene, &scene.camera.eye, &direction, &differential, sampler)
+    };
+    let width = framebuffer.width;
+    let rows = framebuffer.radiance.chunks_mut(width)
+        .zip(framebuffer.accumulation.chunks_mut(width))
+        .enumerate();
+
+    for_each_row(rows, settings.thread_count(), |(y, (radiance, accumulation))| {
+        for x in 0..width {
+            let mut sampler = Sampler::for_pixel(x, y, frame);
+            let (dx, dy, positive) = filter.sample((sampler.next_f32(), sampler.next_f32()));
+
+            let color = trace(x as f32 + 0.5 + dx, y as f32 + 0.5 + dy, &mut sampler);
+
+            accumulation[x].add(color, positive);
+            radiance[x] = accumulation[x].resolve(&filter);
+        }
+    });
+
+    framebuffer.accumulated_frames += 1;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+    use crate::filter::PixelFilter;
+    use crate::whitted::Whitted;
+
+    // Escena sin objetos: todos los rayos ven el mismo cielo
+    fn sky_scene(sky: Color) -> Scene {
+        let camera = Camera::new(Vec3::new(0.0, 0.0, 5.0), Vec3::zeros(), Vec3::new(0.0, 1.0, 0.0));
+        let mut builder = Scene::builder(camera);
+        builder.sky(sky, sky);
+        builder.build().unwrap()
+    }
+
+    fn assert_uniform(framebuffer: &Framebuffer, expected: Color, label: &str) {
+        for (index, pixel) in framebuffer.radiance.iter().enumerate() {
+            let error = (pixel.r - expected.r).abs().max((pixel.g - expected.g).abs()).max((pixel.b - expected.b).abs());
+            assert!(error < 1e-4, "{}: pixel {} is {} instead of {}", label, index, pixel, expected);
+        }
+    }
+
+    #[test]
+    fn constant_radiance_stays_constant_under_every_filter() {
+        let sky = Color::new(0.4, 0.6, 0.9);
+        let scene = sky_scene(sky);
+        let integrator = Whitted::new(1);
+
+        for filter in PixelFilter::ALL {
+            for samples in [2, 4, 16] {
+                let settings = RenderSettings { threads: 1, samples_per_pixel: samples, filter, ..Default::default() };
+                let mut framebuffer = Framebuffer::new(16, 12);
+                render(&mut framebuffer, &scene, &integrator, &settings);
+                assert_uniform(&framebuffer, sky, &format!("{} at {} spp", filter, samples));
+            }
+        }
+    }
+
+    #[test]
+    fn progressive_accumulation_stays_constant_and_resets() {
+        let sky = Color::new(0.4, 0.6, 0.9);
+        let integrator = Whitted::new(1);
+
+        for filter in PixelFilter::ALL {
+            let settings = RenderSettings { threads: 1, filter, ..Default::default() };
+            let mut framebuffer = Framebuffer::new(16, 12);
+            for _ in 0..3 {
+                render_progressive(&mut framebuffer, &sky_scene(sky), &integrator, &settings);
+                assert_uniform(&framebuffer, sky, &format!("progressive {}", filter));
+            }
+
+            framebuffer.reset_accumulation();
+            assert_uniform(&framebuffer, Color::black(), &format!("reset {}", filter));
+            let other = Color::new(1.0, 0.5, 0.25);
+            render_progressive(&mut framebuffer, &sky_scene(other), &integrator, &settings);
+            assert_uniform(&framebuffer, other, &format!("after reset {}", filter));
+        }
+    }
+}
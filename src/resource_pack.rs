@@ -0,0 +1,298 @@
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use zip::ZipArchive;
+
+use crate::color::Color;
+use crate::import::ImportError;
+use crate::material::{BlockFaces, Material};
+use crate::schematic::block_state_key;
+use crate::texture::Texture;
+
+// Color de las caras con `tintindex`, el de la hierba de las llanuras. El
+// paquete guarda esas texturas en gris para teñirlas según el bioma.
+const DEFAULT_TINT: [u8; 3] = [0x91, 0xbd, 0x59];
+// Límite de padres de un modelo y de referencias "#nombre" encadenadas
+const MAX_INDIRECTIONS: usize = 16;
+// Caras en el orden de BlockFaces: arriba, abajo, norte, sur, este, oeste
+const FACES: [&str; 6] = ["up", "down", "north", "south", "east", "west"];
+
+enum Source {
+    Directory(PathBuf),
+    Zip { archive: Mutex<ZipArchive<File>>, prefix: String },
+}
+
+// Paquete de recursos de Minecraft, como carpeta o como zip (el .jar del
+// cliente también sirve). Traduce estados de bloque a materiales por cara
+// siguiendo blockstates/*.json y models/*.json, y guarda cada textura y
+// material ya creado para compartirlo entre bloques.
+pub struct ResourcePack {
+    pub path: PathBuf,
+    source: Source,
+    materials: Mutex<HashMap<(String, bool), Arc<Material>>>,
+}
+
+// Modelo ya resuelto: texturas de la cadena de padres y cubos del primero
+// que los define
+struct Model {
+    textures: Map<String, Value>,
+    elements: Vec<Value>,
+}
+
+// Quita el espacio de nombres: "minecraft:block/stone" -> ("minecraft", "block/stone")
+fn split_id(id: &str) -> (&str, &str) {
+    id.split_once(':').unwrap_or(("minecraft", id))
+}
+
+// Cara del mundo a la que va la cara `face` del modelo tras girarlo `x` y
+// luego `y` grados, en pasos de 90 como en los blockstates
+fn rotate(face: usize, x: i64, y: i64) -> usize {
+    let mut face = face;
+    for _ in 0..x.rem_euclid(360) / 90 {
+        // Giro sobre X: arriba -> norte -> abajo -> sur -> arriba
+        face = match face { 0 => 2, 2 => 1, 1 => 3, 3 => 0, other => other };
+    }
+    for _ in 0..y.rem_euclid(360) / 90 {
+        // Giro sobre Y: norte -> este -> sur -> oeste -> norte
+        face = match face { 2 => 4, 4 => 3, 3 => 5, 5 => 2, other => other };
+    }
+    face
+}
+
+// Comprueba una condición "when" de un multipart contra las propiedades
+fn condition_matches(condition: &Map<String, Value>, properties: &HashMap<&str, &str>) -> bool {
+    if let Some(any) = condition.get("OR").and_then(Value::as_array) {
+        return any.iter().filter_map(Value::as_object).any(|c| condition_matches(c, properties));
+    }
+    if let Some(all) = condition.get("AND").and_then(Value::as_array) {
+        return all.iter().filter_map(Value::as_object).all(|c| condition_matches(c, properties));
+    }
+    condition.iter().all(|(key, expected)| {
+        let expected = match expected {
+            Value::String(value) => value.clone(),
+            other => other.to_string(),
+        };
+        properties.get(key.as_str()).is_some_and(|value| expected.split('|').any(|e| e == *value))
+    })
+}
+
+impl ResourcePack {
+    pub fn open(path: &Path) -> Result<Self, ImportError> {
+        let source = if path.is_dir() {
+            Source::Directory(path.to_path_buf())
+        } else {
+            let file = File::open(path).map_err(|err| ImportError::new(path, err.to_string()))?;
+            let archive = ZipArchive::new(file).map_err(|err| ImportError::new(path, format!("not a zip file: {}", err)))?;
+            // Algunos zip guardan el paquete dentro de una carpeta
+            let prefix = archive.file_names()
+                .filter_map(|name| name.strip_suffix("pack.mcmeta"))
+                .min_by_key(|prefix| prefix.len())
+                .unwrap_or("")
+                .to_string();
+            Source::Zip { archive: Mutex::new(archive), prefix }
+        };
+
+        Ok(ResourcePack {
+            path: path.to_path_buf(),
+            source,
+            materials: Mutex::new(HashMap::new()),
+        })
+    }
+
+    // Contenido de un archivo del paquete, o None si no existe
+    fn read(&self, file: &str) -> Result<Option<Vec<u8>>, ImportError> {
+        let error = |err: &dyn std::fmt::Display| ImportError::new(&self.path, format!("{}: {}", file, err));
+        let mut data = Vec::new();
+        match &self.source {
+            Source::Directory(root) => match std::fs::read(root.join(file)) {
+                Ok(contents) => data = contents,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+                Err(err) => return Err(error(&err)),
+            },
+            Source::Zip { archive, prefix } => {
+                let mut archive = archive.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                let mut entry = match archive.by_name(&format!("{}{}", prefix, file)) {
+                    Ok(entry) => entry,
+                    Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+                    Err(err) => return Err(error(&err)),
+                };
+                entry.read_to_end(&mut data).map_err(|err| error(&err))?;
+            }
+        }
+        Ok(Some(data))
+    }
+
+    fn json(&self, file: &str) -> Result<Option<Value>, ImportError> {
+        match self.read(file)? {
+            Some(data) => serde_json::from_slice(&data)
+                .map(Some)
+                .map_err(|err| ImportError::new(&self.path, format!("{}: {}", file, err))),
+            None => Ok(None),
+        }
+    }
+
+    // Material de una textura del paquete ("minecraft:block/stone"), teñido
+    // si la cara lo pide
+    fn material(&self, texture: &str, tinted: bool) -> Result<Option<Arc<Material>>, ImportError> {
+        let key = (texture.to_string(), tinted);
+        if let Some(material) = self.materials.lock().unwrap_or_else(|p| p.into_inner()).get(&key) {
+            return Ok(Some(Arc::clone(material)));
+        }
+
+        let (namespace, name) = split_id(texture);
+        let file = format!("assets/{}/textures/{}.png", namespace, name);
+        let Some(data) = self.read(&file)? else {
+            return Ok(None);
+        };
+        let mut image = image::load_from_memory(&data)
+            .map_err(|err| ImportError::new(&self.path, format!("{}: {}", file, err)))?
+            .to_rgba8();
+        // Las texturas animadas apilan los fotogramas en vertical; se usa el primero
+        let (width, height) = image.dimensions();
+        if height > width && width > 0 && height % width == 0 {
+            image = image::imageops::crop_imm(&image, 0, 0, width, width).to_image();
+        }
+        if tinted {
+            for pixel in image.pixels_mut() {
+                for channel in 0..3 {
+                    pixel[channel] = (pixel[channel] as u16 * DEFAULT_TINT[channel] as u16 / 255) as u8;
+                }
+            }
+        }
+
+        let material = Arc::new(Material::new(
+            Color::new(1.0, 1.0, 1.0),
+            1.0,
+            [0.9, 0.05, 0.0, 0.0],
+            1.0,
+            Some(Arc::new(Texture::from_image(image::DynamicImage::ImageRgba8(image)))),
+            Color::black(),
+        ));
+        self.materials.lock().unwrap_or_else(|p| p.into_inner()).insert(key, Arc::clone(&material));
+        Ok(Some(material))
+    }
+
+    // Recorre la cadena de padres de un modelo
+    fn model(&self, id: &str) -> Result<Option<Model>, ImportError> {
+        let mut textures = Map::new();
+        let mut elements = None;
+        let mut next = Some(id.to_string());
+
+        for _ in 0..MAX_INDIRECTIONS {
+            let Some(current) = next.take() else {
+                break;
+            };
+            let (namespace, name) = split_id(&current);
+            let Some(model) = self.json(&format!("assets/{}/models/{}.json", namespace, name))? else {
+                // Los modelos "builtin/..." no tienen archivo
+                break;
+            };
+            // Las texturas del hijo tienen prioridad sobre las del padre
+            for (key, value) in model.get("textures").and_then(Value::as_object).into_iter().flatten() {
+                textures.entry(key.clone()).or_insert_with(|| value.clone());
+            }
+            if elements.is_none() {
+                elements = model.get("elements").and_then(Value::as_array).cloned();
+            }
+            next = model.get("parent").and_then(Value::as_str).map(str::to_string);
+        }
+
+        Ok(elements.map(|elements| Model { textures, elements }))
+    }
+
+    // Sigue las referencias "#lado" hasta llegar al nombre de una textura
+    fn resolve_texture(model: &Model, reference: &str) -> Option<String> {
+        let mut reference = reference.to_string();
+        for _ in 0..MAX_INDIRECTIONS {
+            match reference.strip_prefix('#') {
+                Some(key) => reference = model.textures.get(key)?.as_str()?.to_string(),
+                None => return Some(reference),
+            }
+        }
+        None
+    }
+
+    // Variante del blockstate que corresponde al estado: modelo y giros
+    fn variant(&self, state: &str) -> Result<Option<Value>, ImportError> {
+        let key = block_state_key(state);
+        let (name, properties) = match key.split_once('[') {
+            Some((name, rest)) => (name, rest.trim_end_matches(']')),
+            None => (key.as_str(), ""),
+        };
+        let properties: HashMap<&str, &str> = properties.split(',').filter_map(|p| p.split_once('=')).collect();
+
+        let (namespace, block) = split_id(name);
+        let Some(blockstate) = self.json(&format!("assets/{}/blockstates/{}.json", namespace, block))? else {
+            return Ok(None);
+        };
+
+        // Una lista de variantes ponderadas se reduce a la primera
+        let first = |value: &Value| match value {
+            Value::Array(options) => options.first().cloned(),
+            other => Some(other.clone()),
+        };
+
+        if let Some(variants) = blockstate.get("variants").and_then(Value::as_object) {
+            let found = variants.iter().find(|(condition, _)| {
+                condition.split(',').filter(|c| !c.is_empty() && *c != "normal").all(|condition| {
+                    condition.split_once('=').is_some_and(|(k, v)| properties.get(k) == Some(&v))
+                })
+            });
+            return Ok(found.and_then(|(_, variant)| first(variant)));
+        }
+
+        // De un multipart sólo se usa la primera parte que aplica
+        if let Some(parts) = blockstate.get("multipart").and_then(Value::as_array) {
+            let found = parts.iter().find(|part| match part.get("when").and_then(Value::as_object) {
+                Some(condition) => condition_matches(condition, &properties),
+                None => true,
+            });
+            return Ok(found.and_then(|part| part.get("apply")).and_then(first));
+        }
+
+        Ok(None)
+    }
+
+    // Materiales por cara de un estado de bloque, o None si el paquete no
+    // sabe dibujarlo. Cada cara usa la primera caja del modelo que la tiene;
+    // las coordenadas `uv` y los giros de textura dentro de la cara no se
+    // aplican. Todo bloque ocupa el cubo completo: las hojas y el cristal
+    // quedan bien porque la rejilla no dibuja los texeles transparentes, pero
+    // las flores o los raíles se ven como un cubo con su textura recortada.
+    pub fn block_faces(&self, state: &str) -> Result<Option<BlockFaces>, ImportError> {
+        let Some(variant) = self.variant(state)? else {
+            return Ok(None);
+        };
+        let Some(model) = variant.get("model").and_then(Value::as_str) else {
+            return Ok(None);
+        };
+        let Some(model) = self.model(model)? else {
+            return Ok(None);
+        };
+        let x = variant.get("x").and_then(Value::as_i64).unwrap_or(0);
+        let y = variant.get("y").and_then(Value::as_i64).unwrap_or(0);
+
+        let mut faces: [Option<Arc<Material>>; 6] = Default::default();
+        for (index, face) in FACES.iter().enumerate() {
+            let target = rotate(index, x, y);
+            let Some(description) = model.elements.iter().find_map(|element| element.get("faces")?.get(face)) else {
+                continue;
+            };
+            let Some(texture) = description.get("texture").and_then(Value::as_str)
+                .and_then(|reference| Self::resolve_texture(&model, reference)) else {
+                continue;
+            };
+            faces[target] = self.material(&texture, description.get("tintindex").is_some())?;
+        }
+
+        // Las caras que faltan toman la textura de otra cara del bloque
+        let Some(fallback) = faces.iter().flatten().next().cloned() else {
+            return Ok(None);
+        };
+        let [top, bottom, north, south, east, west] = faces.map(|face| face.unwrap_or_else(|| Arc::clone(&fallback)));
+        Ok(Some(BlockFaces { top, bottom, north, south, east, west }))
+    }
+}
@@ -0,0 +1,47 @@
+// Generador pseudoaleatorio PCG32. Cada píxel recibe su propia semilla, así que
+// el resultado no depende del orden ni del número de hilos.
+pub struct Sampler {
+    state: u64,
+}
+
+const MULTIPLIER: u64 = 6364136223846793005;
+const INCREMENT: u64 = 1442695040888963407;
+
+impl Sampler {
+    pub fn new(seed: u64) -> Self {
+        let mut sampler = Sampler { state: 0 };
+        sampler.next_u32();
+        sampler.state = sampler.state.wrapping_add(seed);
+        sampler.next_u32();
+        sampler
+    }
+
+    // `frame` separa las secuencias de cuadros sucesivos en el render progresivo
+    pub fn for_pixel(x: usize, y: usize, frame: u32) -> Self {
+        let pixel = ((y as u64) << 32) | x as u64;
+        Sampler::new(pixel ^ (frame as u64).wrapping_mul(0x9E3779B97F4A7C15))
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.state = old.wrapping_mul(MULTIPLIER).wrapping_add(INCREMENT);
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        let rot = (old >> 59) as u32;
+        xorshifted.rotate_right(rot)
+    }
+
+    // Número uniforme en [0, 1)
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u32() >> 8) as f32 * (1.0 / (1 << 24) as f32)
+    }
+
+    // Muestra `index` de `count` repartidas en una rejilla de estratos, con una
+    // posición aleatoria dentro de cada celda.
+    pub fn stratified(&mut self, index: usize, count: usize) -> (f32, f32) {
+        let columns = (count as f32).sqrt().ceil().max(1.0) as usize;
+        let rows = count.div_ceil(columns).max(1);
+        let u = ((index % columns) as f32 + self.next_f32()) / columns as f32;
+        let v = ((index / columns) as f32 + self.next_f32()) / rows as f32;
+        (u, v)
+    }
+}
@@ -0,0 +1,264 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::anvil::AnvilWorld;
+use crate::bvh::Bvh;
+use crate::camera::Camera;
+use crate::color::Color;
+use crate::import::ImportError;
+use crate::light::LightSource;
+use crate::material::Material;
+use crate::ray_intersect::RayIntersect;
+use crate::texture::Texture;
+
+// Luces y color del cielo de un momento del día
+pub struct Lighting {
+    pub lights: Vec<Box<dyn LightSource>>,
+    pub sky_color: Color,
+}
+
+impl Lighting {
+    fn new() -> Self {
+        Lighting {
+            lights: Vec::new(),
+            sky_color: Color::black(),
+        }
+    }
+}
+
+// Todo lo que hace falta para renderizar: geometría, materiales y texturas con
+// nombre, cámara e iluminación de día y de noche. `is_day` elige cuál se usa.
+// Los mundos de Minecraft también están en `objects`; `worlds` los guarda
+// aparte para cargar sus chunks según dónde esté la cámara.
+pub struct Scene {
+    pub objects: Vec<Box<dyn RayIntersect>>,
+    pub worlds: Vec<Arc<AnvilWorld>>,
+    pub materials: BTreeMap<String, Arc<Material>>,
+    pub textures: BTreeMap<String, Arc<Texture>>,
+    pub camera: Camera,
+    pub day: Lighting,
+    pub night: Lighting,
+    pub is_day: bool,
+}
+
+#[derive(Debug)]
+pub struct InvalidScene(pub String);
+
+impl fmt::Display for InvalidScene {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Scene {
+    pub fn builder(camera: Camera) -> SceneBuilder {
+        SceneBuilder {
+            scene: Scene {
+                objects: Vec::new(),
+                worlds: Vec::new(),
+                materials: BTreeMap::new(),
+                textures: BTreeMap::new(),
+                camera,
+                day: Lighting::new(),
+                night: Lighting::new(),
+                is_day: true,
+            },
+        }
+    }
+
+    pub fn lighting(&self) -> &Lighting {
+        if self.is_day { &self.day } else { &self.night }
+    }
+
+    // Agrupa todos los objetos en un BVH para no probar cada rayo contra cada objeto
+    pub fn accelerate(&mut self) {
+        let objects = std::mem::take(&mut self.objects);
+        self.objects = vec![Box::new(Bvh::new(objects))];
+    }
+
+    // Carga los chunks cercanos al punto que mira la cámara y descarta los
+    // lejanos. Devuelve si cambió la geometría.
+    pub fn stream_chunks(&self) -> Result<bool, ImportError> {
+        let mut changed = false;
+        for world in &self.worlds {
+            changed |= world.load_around(&self.camera.center)?;
+        }
+        Ok(changed)
+    }
+
+    pub fn validate(&self) -> Result<(), InvalidScene> {
+        let camera = &self.camera;
+        if !(camera.eye.iter().chain(camera.center.iter()).chain(camera.up.iter()).all(|c| c.is_finite())) {
+            return Err(InvalidScene("camera has non-finite coordinates".to_string()));
+        }
+        let forward = camera.center - camera.eye;
+        if forward.magnitude() < 1e-6 {
+            return Err(InvalidScene("camera eye and center are the same point".to_string()));
+        }
+        if forward.cross(&camera.up).magnitude() < 1e-6 {
+            return Err(InvalidScene("camera up vector is parallel to the view direction".to_string()));
+        }
+        if !(camera.fov > 0.0 && camera.fov < std::f32::consts::PI) {
+            return Err(InvalidScene("camera field of view must be between 0 and 180 degrees".to_string()));
+        }
+
+        for (name, lighting) in [("day", &self.day), ("night", &self.night)] {
+            if !valid_color(lighting.sky_color) {
+                return Err(InvalidScene(format!("{} sky color must be finite and non-negative", name)));
+            }
+            for (index, light) in lighting.lights.iter().enumerate() {
+                light.validate().map_err(|message| InvalidScene(format!("{} light {}: {}", name, index + 1, message)))?;
+            }
+        }
+
+        for (index, object) in self.objects.iter().enumerate() {
+            object.validate().map_err(|message| InvalidScene(format!("object {}: {}", index + 1, message)))?;
+        }
+
+        for (name, texture) in &self.textures {
+            if texture.width() == 0 || texture.height() == 0 {
+                return Err(InvalidScene(format!("texture '{}' is empty", name)));
+            }
+        }
+
+        for (name, material) in &self.materials {
+            validate_material(material).map_err(|message| InvalidScene(format!("material '{}': {}", name, message)))?;
+        }
+
+        Ok(())
+    }
+}
+
+fn valid_color(color: Color) -> bool {
+    [color.r, color.g, color.b].iter().all(|c| c.is_finite() && *c >= 0.0)
+}
+
+fn validate_material(material: &Material) -> Result<(), String> {
+    if material.albedo.iter().any(|a| !a.is_finite() || *a < 0.0) {
+        return Err("albedo must be finite and non-negative".to_string());
+    }
+    let [_, _, reflectivity, transparency] = material.albedo;
+    if reflectivity + transparency > 1.0 {
+        return Err("reflectivity plus transparency exceeds 1".to_string());
+    }
+    if transparency > 0.0 && material.refractive_index <= 0.0 {
+        return Err("transparent materials need a positive refractive index".to_string());
+    }
+    if !valid_color(material.diffuse) || !valid_color(material.emissive_color) {
+        return Err("colors must be finite and non-negative".to_string());
+    }
+    Ok(())
+}
+
+// Construye una escena paso a paso. Las texturas y materiales se registran con
+// un nombre y devuelven la referencia compartida que usan los objetos.
+pub struct SceneBuilder {
+    scene: Scene,
+}
+
+impl SceneBuilder {
+    pub fn texture(&mut self, name: &str, texture: Texture) -> Arc<Texture> {
+        let texture = Arc::new(texture);
+        self.scene.textures.insert(name.to_string(), Arc::clone(&texture));
+        texture
+    }
+
+    pub fn material(&mut self, name: &str, material: Material) -> Arc<Material> {
+        let material = Arc::new(material);
+        self.scene.materials.insert(name.to_string(), Arc::clone(&material));
+        material
+    }
+
+    pub fn object(&mut self, object: Box<dyn RayIntersect>) -> &mut Self {
+        self.scene.objects.push(object);
+        self
+    }
+
+    pub fn world(&mut self, world: Arc<AnvilWorld>) -> &mut Self {
+        self.scene.objects.push(Box::new(Arc::clone(&world)));
+        self.scene.worlds.push(world);
+        self
+    }
+
+    pub fn light(&mut self, light: Box<dyn LightSource>) -> &mut Self {
+        self.scene.day.lights.push(light);
+        self
+    }
+
+    pub fn night_light(&mut self, light: Box<dyn LightSource>) -> &mut Self {
+        self.scene.night.lights.push(light);
+        self
+    }
+
+    pub fn sky(&mut self, day: Color, night: Color) -> &mut Self {
+        self.scene.day.sky_color = day;
+        self.scene.night.sky_color = night;
+        self
+    }
+
+    pub fn camera(&mut self, camera: Camera) -> &mut Self {
+        self.scene.camera = camera;
+        self
+    }
+
+    pub fn build(self) -> Result<Scene, InvalidScene> {
+        self.scene.validate()?;
+        Ok(self.scene)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra_glm::Vec3;
+
+    use crate::light::{DirectionalLight, PointLight};
+    use crate::plane::Plane;
+    use crate::sphere::Sphere;
+
+    fn builder() -> SceneBuilder {
+        let camera = Camera::new(Vec3::new(0.0, 1.0, 5.0), Vec3::zeros(), Vec3::new(0.0, 1.0, 0.0));
+        Scene::builder(camera)
+    }
+
+    fn material() -> Arc<Material> {
+        Arc::new(Material::new(Color::new(0.5, 0.5, 0.5), 10.0, [1.0, 0.0, 0.0, 0.0], 1.0, None, Color::black()))
+    }
+
+    fn error(builder: SceneBuilder) -> String {
+        builder.build().err().expect("scene should be invalid").0
+    }
+
+    #[test]
+    fn accepts_valid_lights_and_objects() {
+        let mut scene = builder();
+        scene.light(Box::new(DirectionalLight::new(Vec3::new(0.0, -1.0, 0.0), Color::new(1.0, 1.0, 1.0), 1.0)));
+        scene.object(Box::new(Plane::new(Vec3::zeros(), Vec3::new(0.0, 1.0, 0.0), material())));
+        scene.object(Box::new(Sphere::new(Vec3::zeros(), 1.0, material())));
+        assert!(scene.build().is_ok());
+    }
+
+    #[test]
+    fn rejects_degenerate_lights() {
+        let mut scene = builder();
+        scene.light(Box::new(DirectionalLight::new(Vec3::zeros(), Color::new(1.0, 1.0, 1.0), 1.0)));
+        assert!(error(scene).starts_with("day light 1: direction"));
+
+        let mut scene = builder();
+        scene.night_light(Box::new(PointLight::new(Vec3::zeros(), Color::new(1.0, 1.0, 1.0), f32::NAN)));
+        assert!(error(scene).starts_with("night light 1: intensity"));
+    }
+
+    #[test]
+    fn rejects_degenerate_objects() {
+        let mut scene = builder();
+        scene.object(Box::new(Sphere::new(Vec3::zeros(), 1.0, material())));
+        scene.object(Box::new(Plane::new(Vec3::zeros(), Vec3::zeros(), material())));
+        assert!(error(scene).starts_with("object 2: plane normal"));
+
+        let mut scene = builder();
+        scene.object(Box::new(Sphere::new(Vec3::zeros(), 0.0, material())));
+        assert!(error(scene).starts_with("object 1: sphere radius"));
+    }
+}
@@ -0,0 +1,733 @@
+use nalgebra_glm::{Mat4, Vec3};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
+use toml::Spanned;
+
+use crate::aabb::Aabb;
+use crate::anvil::{AnvilWorld, WorldOptions};
+use crate::camera::{Camera, DEFAULT_FOV};
+use crate::color::Color;
+use crate::cube::Cube;
+use crate::disk::Disk;
+use crate::gltf_import::load_gltf;
+use crate::light::{
+    Attenuation, DirectionalLight, LightSource, PointLight, RectLight, SphereLight, SpotLight,
+    DEFAULT_AREA_SAMPLES, NO_ATTENUATION,
+};
+use crate::material::{BlockFaces, Material};
+use crate::ray_intersect::RayIntersect;
+use crate::obj::load_obj;
+use crate::plane::Plane;
+use crate::rectangle::Rectangle;
+use crate::resource_pack::ResourcePack;
+use crate::scene::Scene;
+use crate::schematic::{load_structure, BlockTable};
+use crate::sphere::Sphere;
+use crate::square::Square;
+use crate::texture::{FilterMode, Texture, WrapMode};
+use crate::triangle::Triangle;
+use crate::vox::{load_vox, VoxOptions};
+use crate::voxel_grid::{cell_count, VoxelGrid, DEFAULT_BLOCK_SIZE, MAX_CELLS};
+
+pub const DEFAULT_SCENE: &str = "scenes/diorama.toml";
+// Cielos para los modelos que se abren sin archivo de escena
+const DEFAULT_DAY_SKY: Color = Color::new(0.0578, 0.2705, 0.7758);
+const DEFAULT_NIGHT_SKY: Color = Color::new(0.0097, 0.0097, 0.162);
+
+#[derive(Debug)]
+pub struct SceneError {
+    pub path: String,
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{}:{}: {}", self.path, line, self.message),
+            None => write!(f, "{}: {}", self.path, self.message),
+        }
+    }
+}
+
+// Una textura es sólo la ruta del archivo o una tabla que además elige cómo
+// se filtra y qué pasa fuera de [0, 1]
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TextureDesc {
+    Path(String),
+    Options(TextureOptions),
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TextureOptions {
+    path: String,
+    filter: Option<String>,
+    wrap: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SceneDesc {
+    camera: CameraDesc,
+    sky: SkyDesc,
+    lights: Vec<Spanned<LightDesc>>,
+    night_lights: Option<Vec<Spanned<LightDesc>>>,
+    // Paquete de recursos (carpeta o zip) para los bloques de estructuras y
+    // mundos que no aparecen en `block_materials`
+    resource_pack: Option<Spanned<String>>,
+    #[serde(default)]
+    textures: BTreeMap<String, Spanned<TextureDesc>>,
+    #[serde(default)]
+    materials: BTreeMap<String, MaterialDesc>,
+    #[serde(default)]
+    objects: Vec<ObjectDesc>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CameraDesc {
+    eye: [f32; 3],
+    center: [f32; 3],
+    #[serde(default = "default_up")]
+    up: [f32; 3],
+    // Campo de visión vertical en grados
+    fov: Option<f32>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SkyDesc {
+    day: [u8; 3],
+    night: [u8; 3],
+}
+
+// Los ángulos de los focos se escriben en grados
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase", deny_unknown_fields)]
+enum LightDesc {
+    Point {
+        position: [f32; 3],
+        color: [u8; 3],
+        #[serde(default = "default_intensity")]
+        intensity: f32,
+        #[serde(default = "default_attenuation")]
+        attenuation: Attenuation,
+    },
+    Directional {
+        direction: [f32; 3],
+        color: [u8; 3],
+        #[serde(default = "default_intensity")]
+        intensity: f32,
+    },
+    Spot {
+        position: [f32; 3],
+        direction: [f32; 3],
+        color: [u8; 3],
+        #[serde(default = "default_intensity")]
+        intensity: f32,
+        inner_angle: f32,
+        outer_angle: f32,
+        #[serde(default = "default_attenuation")]
+        attenuation: Attenuation,
+    },
+    Rect {
+        corner: [f32; 3],
+        edge_u: [f32; 3],
+        edge_v: [f32; 3],
+        color: [u8; 3],
+        #[serde(default = "default_intensity")]
+        intensity: f32,
+        #[serde(default = "default_attenuation")]
+        attenuation: Attenuation,
+        #[serde(default = "default_area_samples")]
+        samples: usize,
+    },
+    Sphere {
+        center: [f32; 3],
+        radius: f32,
+        color: [u8; 3],
+        #[serde(default = "default_intensity")]
+        intensity: f32,
+        #[serde(default = "default_attenuation")]
+        attenuation: Attenuation,
+        #[serde(default = "default_area_samples")]
+        samples: usize,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MaterialDesc {
+    diffuse: [u8; 3],
+    #[serde(default)]
+    specular: f32,
+    albedo: [f32; 4],
+    #[serde(default)]
+    refractive_index: f32,
+    texture: Option<Spanned<String>>,
+    #[serde(default)]
+    emissive: [u8; 3],
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ObjectDesc {
+    #[serde(rename = "type")]
+    kind: Spanned<String>,
+    material: Option<Spanned<String>>,
+    size: Option<f32>,
+    center: Option<[f32; 3]>,
+    centers: Option<Vec<[f32; 3]>>,
+    origin: Option<[f32; 3]>,
+    radius: Option<f32>,
+    normal: Option<[f32; 3]>,
+    corner: Option<[f32; 3]>,
+    edge_u: Option<[f32; 3]>,
+    edge_v: Option<[f32; 3]>,
+    vertices: Option<[[f32; 3]; 3]>,
+    normals: Option<[[f32; 3]; 3]>,
+    uvs: Option<[[f32; 2]; 3]>,
+    palette: Option<Vec<Spanned<String>>>,
+    blocks: Option<Vec<Spanned<[usize; 4]>>>,
+    path: Option<Spanned<String>>,
+    scale: Option<f32>,
+    use_camera: Option<bool>,
+    // Bloque -> material de la escena: índice de la paleta (1 a 255) en un
+    // .vox o estado de bloque en una estructura o mundo de Minecraft
+    block_materials: Option<BTreeMap<Spanned<String>, Spanned<String>>>,
+    faces: Option<FacesDesc>,
+    // Tamaño de cada repetición de la textura de un cuadrado
+    tile: Option<f32>,
+    // Caja de un mundo de Minecraft en coordenadas de bloque, ambas incluidas
+    min: Option<[i32; 3]>,
+    max: Option<[i32; 3]>,
+    // Radio en chunks que se mantiene cargado alrededor de la cámara
+    view_distance: Option<u32>,
+}
+
+// Material de cada cara de un cubo; `sides` vale para las cuatro laterales
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FacesDesc {
+    top: Option<Spanned<String>>,
+    bottom: Option<Spanned<String>>,
+    sides: Option<Spanned<String>>,
+    north: Option<Spanned<String>>,
+    south: Option<Spanned<String>>,
+    east: Option<Spanned<String>>,
+    west: Option<Spanned<String>>,
+}
+
+fn default_up() -> [f32; 3] {
+    [0.0, 1.0, 0.0]
+}
+
+fn default_intensity() -> f32 {
+    1.0
+}
+
+fn default_attenuation() -> Attenuation {
+    NO_ATTENUATION
+}
+
+fn default_area_samples() -> usize {
+    DEFAULT_AREA_SAMPLES
+}
+
+fn vec3(v: [f32; 3]) -> Vec3 {
+    Vec3::new(v[0], v[1], v[2])
+}
+
+fn color(c: [u8; 3]) -> Color {
+    Color::from_srgb8(c[0], c[1], c[2])
+}
+
+fn light(desc: &LightDesc) -> Box<dyn LightSource> {
+    match *desc {
+        LightDesc::Point { position, color: c, intensity, attenuation } => {
+            let mut light = PointLight::new(vec3(position), color(c), intensity);
+            light.attenuation = attenuation;
+            Box::new(light)
+        }
+        LightDesc::Directional { direction, color: c, intensity } => {
+            Box::new(DirectionalLight::new(vec3(direction), color(c), intensity))
+        }
+        LightDesc::Spot { position, direction, color: c, intensity, inner_angle, outer_angle, attenuation } => {
+            let mut light = SpotLight::new(
+                vec3(position),
+                vec3(direction),
+                color(c),
+                intensity,
+                inner_angle.to_radians(),
+                outer_angle.to_radians(),
+            );
+            light.attenuation = attenuation;
+            Box::new(light)
+        }
+        LightDesc::Rect { corner, edge_u, edge_v, color: c, intensity, attenuation, samples } => {
+            let mut light = RectLight::new(vec3(corner), vec3(edge_u), vec3(edge_v), color(c), intensity);
+            light.attenuation = attenuation;
+            light.samples = samples;
+            Box::new(light)
+        }
+        LightDesc::Sphere { center, radius, color: c, intensity, attenuation, samples } => {
+            let mut light = SphereLight::new(vec3(center), radius, color(c), intensity);
+            light.attenuation = attenuation;
+            light.samples = samples;
+            Box::new(light)
+        }
+    }
+}
+
+pub fn load(path: &str) -> Result<Scene, SceneError> {
+    let extension = Path::new(path).extension().and_then(|ext| ext.to_str()).unwrap_or("");
+    if extension.eq_ignore_ascii_case("gltf") || extension.eq_ignore_ascii_case("glb") {
+        return gltf_scene(path);
+    }
+
+    let source = std::fs::read_to_string(path).map_err(|err| SceneError {
+        path: path.to_string(),
+        line: None,
+        message: err.to_string(),
+    })?;
+    let base_dir = Path::new(path).parent().unwrap_or(Path::new("."));
+    parse(&source, path, base_dir)
+}
+
+// Abre un glTF como escena completa. Si el archivo no trae cámara se encuadra
+// el modelo, y si no trae luces se añade un sol.
+fn gltf_scene(path: &str) -> Result<Scene, SceneError> {
+    let error = |message: String| SceneError {
+        path: path.to_string(),
+        line: None,
+        message,
+    };
+    let import = load_gltf(Path::new(path), &Mat4::identity()).map_err(|err| error(err.message))?;
+
+    let bounds = import.meshes.iter().fold(Aabb::empty(), |bounds, mesh| bounds.union(&mesh.bounds()));
+    let camera = match import.cameras.into_iter().next() {
+        Some(camera) => camera,
+        None if bounds.is_finite() => {
+            let center = bounds.center();
+            let radius = ((bounds.max - bounds.min).magnitude() * 0.5).max(1e-3);
+            let distance = radius / (DEFAULT_FOV * 0.5).sin();
+            let eye = center + Vec3::new(0.0, 0.5, 1.0).normalize() * distance;
+            Camera::new(eye, center, Vec3::new(0.0, 1.0, 0.0))
+        }
+        None => return Err(error("file has no meshes and no camera".to_string())),
+    };
+
+    let mut builder = Scene::builder(camera);
+    for mesh in import.meshes {
+        builder.object(Box::new(mesh));
+    }
+
+    let mut lights = import.lights;
+    if lights.is_empty() {
+        lights.push(Arc::new(DirectionalLight::new(Vec3::new(-1.0, -2.0, -1.0), Color::new(1.0, 1.0, 1.0), 1.0)));
+    }
+    for light in lights {
+        builder.light(Box::new(Arc::clone(&light)));
+        builder.night_light(Box::new(light));
+    }
+    builder.sky(DEFAULT_DAY_SKY, DEFAULT_NIGHT_SKY);
+
+    builder.build().map_err(|err| error(err.to_string()))
+}
+
+pub fn parse(source: &str, path: &str, base_dir: &Path) -> Result<Scene, SceneError> {
+    let error = |span: Option<Range<usize>>, message: String| SceneError {
+        path: path.to_string(),
+        line: span.map(|span| line_of(source, span.start)),
+        message,
+    };
+
+    let desc: SceneDesc = toml::from_str(source)
+        .map_err(|err| error(err.span(), err.message().to_string()))?;
+
+    let mut camera = Camera::new(vec3(desc.camera.eye), vec3(desc.camera.center), vec3(desc.camera.up));
+    if let Some(fov) = desc.camera.fov {
+        camera.fov = fov.to_radians();
+    }
+    let mut builder = Scene::builder(camera);
+
+    let pack = match &desc.resource_pack {
+        Some(pack_path) => Some(Arc::new(ResourcePack::open(&base_dir.join(pack_path.get_ref()))
+            .map_err(|err| error(Some(pack_path.span()), err.to_string()))?)),
+        None => None,
+    };
+
+    let mut textures = BTreeMap::new();
+    for (name, texture_desc) in &desc.textures {
+        let (texture_path, filter, wrap) = match texture_desc.get_ref() {
+            TextureDesc::Path(path) => (path, None, None),
+            TextureDesc::Options(options) => (&options.path, options.filter.as_ref(), options.wrap.as_ref()),
+        };
+        let filter = filter.map_or(Ok(FilterMode::Nearest), |filter| filter.parse());
+        let wrap = wrap.map_or(Ok(WrapMode::Clamp), |wrap| wrap.parse());
+        let (filter, wrap) = filter.and_then(|filter| Ok((filter, wrap?)))
+            .map_err(|message| error(Some(texture_desc.span()), format!("texture '{}': {}", name, message)))?;
+
+        let full_path = base_dir.join(texture_path);
+        let texture = Texture::load(&full_path).map_err(|err| error(
+            Some(texture_desc.span()),
+            format!("could not load texture '{}' from '{}': {}", name, full_path.display(), err),
+        ))?;
+        textures.insert(name.as_str(), builder.texture(name, texture.with_sampling(filter, wrap)));
+    }
+
+    let mut materials = BTreeMap::new();
+    for (name, material) in &desc.materials {
+        let texture = match &material.texture {
+            Some(texture) => Some(textures.get(texture.get_ref().as_str()).cloned().ok_or_else(|| error(
+                Some(texture.span()),
+                format!("material '{}' uses unknown texture '{}'", name, texture.get_ref()),
+            ))?),
+            None => None,
+        };
+        materials.insert(name.as_str(), builder.material(name, Material::new(
+            color(material.diffuse),
+            material.specular,
+            material.albedo,
+            material.refractive_index,
+            texture,
+            color(material.emissive),
+        )));
+    }
+
+    let find_material = |name: &Spanned<String>| -> Result<Arc<Material>, SceneError> {
+        materials.get(name.get_ref().as_str()).cloned().ok_or_else(|| error(
+            Some(name.span()),
+            format!("unknown material '{}'", name.get_ref()),
+        ))
+    };
+
+    for object in &desc.objects {
+        let kind = object.kind.get_ref().as_str();
+        let missing = |field: &str| error(
+            Some(object.kind.span()),
+            format!("object of type '{}' requires '{}'", kind, field),
+        );
+
+        if kind == "voxels" {
+            let names = object.palette.as_ref().ok_or_else(|| missing("palette"))?;
+            let palette = names.iter().map(find_material).collect::<Result<Vec<_>, _>>()?;
+            builder.object(Box::new(voxel_grid(object, palette, &missing, &error)?));
+            continue;
+        }
+
+        // Las mallas traen sus propios materiales
+        if kind == "mesh" {
+            let mesh_path = object.path.as_ref().ok_or_else(|| missing("path"))?;
+            let full_path = base_dir.join(mesh_path.get_ref());
+            let transform = placement(object);
+            let mesh = match full_path.extension().and_then(|ext| ext.to_str()) {
+                Some(ext) if ext.eq_ignore_ascii_case("obj") => load_obj(&full_path, &transform),
+                _ => return Err(error(
+                    Some(mesh_path.span()),
+                    format!("unsupported mesh format '{}', expected an .obj file", mesh_path.get_ref()),
+                )),
+            };
+            builder.object(Box::new(mesh.map_err(|err| error(Some(mesh_path.span()), err.to_string()))?));
+            continue;
+        }
+
+        // Un .vox se coloca en bloques de `size` con su esquina mínima en `origin`
+        if kind == "vox" {
+            let vox_path = object.path.as_ref().ok_or_else(|| missing("path"))?;
+            let block_size = positive(object, "size", object.size.unwrap_or(DEFAULT_BLOCK_SIZE), &error)?;
+            let mut options = VoxOptions {
+                origin: vec3(object.origin.unwrap_or([0.0, 0.0, 0.0])),
+                block_size,
+                ..VoxOptions::default()
+            };
+            for (index, name) in object.block_materials.iter().flatten() {
+                let palette_index = index.get_ref().parse::<u8>().ok().filter(|&i| i > 0).ok_or_else(|| error(
+                    Some(index.span()),
+                    format!("'{}' is not a palette index between 1 and 255", index.get_ref()),
+                ))?;
+                options.overrides.insert(palette_index, find_material(name)?);
+            }
+            let grids = load_vox(&base_dir.join(vox_path.get_ref()), &options)
+                .map_err(|err| error(Some(vox_path.span()), err.to_string()))?;
+            for grid in grids {
+                builder.object(Box::new(grid));
+            }
+            continue;
+        }
+
+        // Una estructura (.nbt) o esquema (.schem) de Minecraft; los bloques sin
+        // entrada en `block_materials` salen del paquete de recursos o grises
+        if kind == "structure" {
+            let structure_path = object.path.as_ref().ok_or_else(|| missing("path"))?;
+            let block_size = positive(object, "size", object.size.unwrap_or(DEFAULT_BLOCK_SIZE), &error)?;
+            let mut table = BlockTable::new();
+            table.pack = pack.clone();
+            for (state, name) in object.block_materials.iter().flatten() {
+                table.insert(state.get_ref(), find_material(name)?);
+            }
+            let grid = load_structure(
+                &base_dir.join(structure_path.get_ref()),
+                vec3(object.origin.unwrap_or([0.0, 0.0, 0.0])),
+                block_size,
+                &table,
+            )
+            .map_err(|err| error(Some(structure_path.span()), err.to_string()))?;
+            builder.object(Box::new(grid));
+            continue;
+        }
+
+        // Una caja de un mundo guardado; sus chunks se cargan después, según
+        // dónde esté la cámara
+        if kind == "world" {
+            let world_path = object.path.as_ref().ok_or_else(|| missing("path"))?;
+            let mut options = WorldOptions::new(
+                object.min.ok_or_else(|| missing("min"))?,
+                object.max.ok_or_else(|| missing("max"))?,
+            );
+            options.origin = vec3(object.origin.unwrap_or([0.0, 0.0, 0.0]));
+            options.block_size = positive(object, "size", object.size.unwrap_or(DEFAULT_BLOCK_SIZE), &error)?;
+            options.view_distance = object.view_distance.unwrap_or(options.view_distance);
+            options.blocks.pack = pack.clone();
+            for (state, name) in object.block_materials.iter().flatten() {
+                options.blocks.insert(state.get_ref(), find_material(name)?);
+            }
+            let world = AnvilWorld::open(&base_dir.join(world_path.get_ref()), options)
+                .map_err(|err| error(Some(world_path.span()), err.to_string()))?;
+            builder.world(Arc::new(world));
+            continue;
+        }
+
+        // Un glTF aporta mallas, luces (de día y de noche) y, si se pide, su cámara
+        if kind == "gltf" {
+            let gltf_path = object.path.as_ref().ok_or_else(|| missing("path"))?;
+            let import = load_gltf(&base_dir.join(gltf_path.get_ref()), &placement(object))
+                .map_err(|err| error(Some(gltf_path.span()), err.to_string()))?;
+            for mesh in import.meshes {
+                builder.object(Box::new(mesh));
+            }
+            for light in import.lights {
+                builder.light(Box::new(Arc::clone(&light)));
+                builder.night_light(Box::new(light));
+            }
+            if object.use_camera == Some(true) {
+                let camera = import.cameras.into_iter().next().ok_or_else(|| error(
+                    Some(gltf_path.span()),
+                    format!("'{}' has no perspective camera", gltf_path.get_ref()),
+                ))?;
+                builder.camera(camera);
+            }
+            continue;
+        }
+
+        // Los cubos pueden llevar un material por cara; `material` cubre las
+        // caras que `faces` no nombra
+        if kind == "cube" || kind == "cubes" {
+            let faces = cube_faces(object, find_material, missing)?;
+            let size = positive(object, "size", object.size.ok_or_else(|| missing("size"))?, &error)?;
+            let centers = if kind == "cube" {
+                vec![object.center.ok_or_else(|| missing("center"))?]
+            } else {
+                object.centers.clone().ok_or_else(|| missing("centers"))?
+            };
+            for center in centers {
+                builder.object(Box::new(Cube::with_faces(vec3(center), size, faces.clone())));
+            }
+            continue;
+        }
+
+        let material = find_material(object.material.as_ref().ok_or_else(|| missing("material"))?)?;
+
+        match kind {
+            "square" => {
+                let center = object.center.ok_or_else(|| missing("center"))?;
+                let size = positive(object, "size", object.size.ok_or_else(|| missing("size"))?, &error)?;
+                let mut square = Square::new(vec3(center), size, Arc::clone(&material));
+                if let Some(tile) = object.tile {
+                    if tile <= 0.0 {
+                        return Err(error(Some(object.kind.span()), "'tile' must be positive".to_string()));
+                    }
+                    square = square.with_tile(tile);
+                }
+                builder.object(Box::new(square));
+            }
+            "sphere" => {
+                let center = object.center.ok_or_else(|| missing("center"))?;
+                let radius = positive(object, "radius", object.radius.ok_or_else(|| missing("radius"))?, &error)?;
+                builder.object(Box::new(Sphere::new(vec3(center), radius, material)));
+            }
+            "triangle" => {
+                let [a, b, c] = object.vertices.ok_or_else(|| missing("vertices"))?;
+                let (a, b, c) = (vec3(a), vec3(b), vec3(c));
+                nonzero(object, "'vertices' must not be collinear", (b - a).cross(&(c - a)), &error)?;
+                let mut triangle = Triangle::new(a, b, c, material);
+                if let Some([na, nb, nc]) = object.normals {
+                    triangle = triangle.with_normals([vec3(na), vec3(nb), vec3(nc)]);
+                }
+                if let Some([ta, tb, tc]) = object.uvs {
+                    triangle = triangle.with_uvs([(ta[0], ta[1]), (tb[0], tb[1]), (tc[0], tc[1])]);
+                }
+                builder.object(Box::new(triangle));
+            }
+            "plane" => {
+                let center = object.center.ok_or_else(|| missing("center"))?;
+                let normal = vec3(object.normal.ok_or_else(|| missing("normal"))?);
+                let normal = nonzero(object, "'normal' must be non-zero and finite", normal, &error)?;
+                builder.object(Box::new(Plane::new(vec3(center), normal, material)));
+            }
+            "rectangle" => {
+                let corner = object.corner.ok_or_else(|| missing("corner"))?;
+                let edge_u = vec3(object.edge_u.ok_or_else(|| missing("edge_u"))?);
+                let edge_v = vec3(object.edge_v.ok_or_else(|| missing("edge_v"))?);
+                nonzero(object, "'edge_u' and 'edge_v' must not be parallel", edge_u.cross(&edge_v), &error)?;
+                builder.object(Box::new(Rectangle::new(vec3(corner), edge_u, edge_v, material)));
+            }
+            "disk" => {
+                let center = object.center.ok_or_else(|| missing("center"))?;
+                let normal = vec3(object.normal.ok_or_else(|| missing("normal"))?);
+                let normal = nonzero(object, "'normal' must be non-zero and finite", normal, &error)?;
+                let radius = positive(object, "radius", object.radius.ok_or_else(|| missing("radius"))?, &error)?;
+                builder.object(Box::new(Disk::new(vec3(center), normal, radius, material)));
+            }
+            _ => return Err(error(
+                Some(object.kind.span()),
+                format!("unknown object type '{}', expected 'cube', 'cubes', 'square', 'voxels', \
+                 'sphere', 'triangle', 'plane', 'rectangle', 'disk', 'mesh', 'gltf', 'vox', \
+                 'structure' or 'world'", kind),
+            )),
+        }
+    }
+
+    // Las luces se comprueban aquí para que el error lleve la línea
+    let light = |desc: &Spanned<LightDesc>| {
+        let light = light(desc.get_ref());
+        light.validate().map_err(|message| error(Some(desc.span()), format!("light: {}", message)))?;
+        Ok::<_, SceneError>(light)
+    };
+    for desc in &desc.lights {
+        builder.light(light(desc)?);
+    }
+    for desc in desc.night_lights.as_ref().unwrap_or(&desc.lights) {
+        builder.night_light(light(desc)?);
+    }
+    builder.sky(color(desc.sky.day), color(desc.sky.night));
+
+    builder.build().map_err(|err| error(None, err.to_string()))
+}
+
+// Material de cada cara de un cubo: el que nombra `faces` o, si no, `material`
+fn cube_faces<F, M>(object: &ObjectDesc, find_material: F, missing: M) -> Result<BlockFaces, SceneError>
+where
+    F: Fn(&Spanned<String>) -> Result<Arc<Material>, SceneError>,
+    M: Fn(&str) -> SceneError,
+{
+    let base = object.material.as_ref().map(&find_material).transpose()?;
+    let faces = object.faces.as_ref();
+    let sides = faces.and_then(|faces| faces.sides.as_ref());
+    let face = |name: Option<&Spanned<String>>| match name {
+        Some(name) => find_material(name),
+        None => base.clone().ok_or_else(|| missing("material")),
+    };
+
+    Ok(BlockFaces {
+        top: face(faces.and_then(|faces| faces.top.as_ref()))?,
+        bottom: face(faces.and_then(|faces| faces.bottom.as_ref()))?,
+        north: face(faces.and_then(|faces| faces.north.as_ref()).or(sides))?,
+        south: face(faces.and_then(|faces| faces.south.as_ref()).or(sides))?,
+        east: face(faces.and_then(|faces| faces.east.as_ref()).or(sides))?,
+        west: face(faces.and_then(|faces| faces.west.as_ref()).or(sides))?,
+    })
+}
+
+// Un tamaño del objeto (lado, radio...) tiene que ser positivo y finito
+fn positive(
+    object: &ObjectDesc,
+    field: &str,
+    value: f32,
+    error: &dyn Fn(Option<Range<usize>>, String) -> SceneError,
+) -> Result<f32, SceneError> {
+    if value > 0.0 && value.is_finite() {
+        Ok(value)
+    } else {
+        Err(error(Some(object.kind.span()), format!("'{}' must be positive and finite, got {}", field, value)))
+    }
+}
+
+// Una normal, o el producto vectorial que da el área de una figura, no puede
+// ser nula ni infinita
+fn nonzero(
+    object: &ObjectDesc,
+    message: &str,
+    value: Vec3,
+    error: &dyn Fn(Option<Range<usize>>, String) -> SceneError,
+) -> Result<Vec3, SceneError> {
+    let length = value.magnitude();
+    if length > 1e-12 && length.is_finite() {
+        Ok(value)
+    } else {
+        Err(error(Some(object.kind.span()), message.to_string()))
+    }
+}
+
+// Posición de un modelo importado: escala uniforme y después traslación a `origin`
+fn placement(object: &ObjectDesc) -> Mat4 {
+    let origin = vec3(object.origin.unwrap_or([0.0, 0.0, 0.0]));
+    let scale = object.scale.unwrap_or(1.0);
+    nalgebra_glm::scale(&nalgebra_glm::translation(&origin), &Vec3::repeat(scale))
+}
+
+// Los bloques se listan como [x, y, z, i], donde i indexa `palette` desde 0
+fn voxel_grid(
+    object: &ObjectDesc,
+    palette: Vec<Arc<Material>>,
+    missing: &dyn Fn(&str) -> SceneError,
+    error: &dyn Fn(Option<Range<usize>>, String) -> SceneError,
+) -> Result<VoxelGrid, SceneError> {
+    let origin = object.origin.ok_or_else(|| missing("origin"))?;
+    let blocks = object.blocks.as_ref().ok_or_else(|| missing("blocks"))?;
+    if palette.len() >= u16::MAX as usize {
+        return Err(error(Some(object.kind.span()), "voxel palette is limited to 65534 materials".to_string()));
+    }
+
+    let mut dims = [0; 3];
+    for block in blocks {
+        let [x, y, z, index] = *block.get_ref();
+        if index >= palette.len() {
+            return Err(error(
+                Some(block.span()),
+                format!("palette index {} is out of range for {} materials", index, palette.len()),
+            ));
+        }
+        let too_large = || error(
+            Some(block.span()),
+            format!("block ({}, {}, {}) makes the grid larger than {} cells", x, y, z, MAX_CELLS),
+        );
+        let extent = |coordinate: usize, dim: usize| coordinate.checked_add(1).map(|end| dim.max(end));
+        let (Some(sx), Some(sy), Some(sz)) = (extent(x, dims[0]), extent(y, dims[1]), extent(z, dims[2])) else {
+            return Err(too_large());
+        };
+        if cell_count([sx, sy, sz]).is_none() {
+            return Err(too_large());
+        }
+        dims = [sx, sy, sz];
+    }
+
+    let size = positive(object, "size", object.size.ok_or_else(|| missing("size"))?, error)?;
+    let mut grid = VoxelGrid::new(vec3(origin), size, dims, palette.into_iter().map(BlockFaces::uniform).collect())
+        .map_err(|message| error(Some(object.kind.span()), message))?;
+    for block in blocks {
+        let [x, y, z, index] = *block.get_ref();
+        grid.set(x, y, z, index as u16 + 1);
+    }
+    Ok(grid)
+}
+
+fn line_of(source: &str, offset: usize) -> usize {
+    source[..offset.min(source.len())].matches('\n').count() + 1
+}
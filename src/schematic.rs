@@ -0,0 +1,354 @@
+use nalgebra_glm::Vec3;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use crate::color::Color;
+use crate::import::ImportError;
+use crate::material::{BlockFaces, Material};
+use crate::nbt::{self, Tag};
+use crate::resource_pack::ResourcePack;
+use crate::voxel_grid::{cell_count, VoxelGrid};
+
+// Bloques que no se dibujan
+const AIR_BLOCKS: [&str; 4] = ["minecraft:air", "minecraft:cave_air", "minecraft:void_air", "minecraft:structure_void"];
+
+// Nombre completo de un bloque, con espacio de nombres y propiedades ordenadas:
+// "obsidian" pasa a "minecraft:obsidian" y
+// "minecraft:stairs[half=top,facing=east]" a "minecraft:stairs[facing=east,half=top]"
+pub fn block_state_key(state: &str) -> String {
+    let (name, properties) = match state.split_once('[') {
+        Some((name, rest)) => (name, rest.trim_end_matches(']')),
+        None => (state, ""),
+    };
+    let name = name.trim();
+    let mut key = if name.contains(':') { name.to_string() } else { format!("minecraft:{}", name) };
+
+    let mut properties: Vec<&str> = properties.split(',').map(str::trim).filter(|p| !p.is_empty()).collect();
+    if !properties.is_empty() {
+        properties.sort_unstable();
+        key.push('[');
+        key.push_str(&properties.join(","));
+        key.push(']');
+    }
+    key
+}
+
+// Tabla de bloque a material. Una entrada puede nombrar un estado concreto
+// ("nether_portal[axis=z]") o el bloque entero ("nether_portal"); el estado
+// concreto tiene prioridad. Los bloques sin entrada se buscan en `pack`, si
+// hay uno, y si no usan `default`.
+pub struct BlockTable {
+    pub materials: HashMap<String, BlockFaces>,
+    pub default: BlockFaces,
+    pub pack: Option<Arc<ResourcePack>>,
+    // Estados ya resueltos con el paquete; None si el paquete no los tiene
+    resolved: Mutex<HashMap<String, Option<BlockFaces>>>,
+}
+
+impl BlockTable {
+    pub fn new() -> Self {
+        BlockTable {
+            materials: HashMap::new(),
+            default: BlockFaces::uniform(Arc::new(Material::new(
+                Color::new(0.5, 0.5, 0.5),
+                1.0,
+                [1.0, 0.0, 0.0, 0.0],
+                1.0,
+                None,
+                Color::black(),
+            ))),
+            pack: None,
+            resolved: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn insert(&mut self, state: &str, material: Arc<Material>) {
+        self.materials.insert(block_state_key(state), BlockFaces::uniform(material));
+    }
+
+    // Materiales de un estado de bloque, o None si es aire
+    pub fn get(&self, state: &str) -> Result<Option<BlockFaces>, ImportError> {
+        let key = block_state_key(state);
+        let name = key.split('[').next().unwrap_or(&key);
+        if AIR_BLOCKS.contains(&name) {
+            return Ok(None);
+        }
+        if let Some(faces) = self.materials.get(&key).or_else(|| self.materials.get(name)) {
+            return Ok(Some(faces.clone()));
+        }
+
+        let Some(pack) = &self.pack else {
+            return Ok(Some(self.default.clone()));
+        };
+        let mut resolved = self.resolved.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let faces = match resolved.get(&key) {
+            Some(faces) => faces.clone(),
+            None => {
+                let faces = pack.block_faces(&key)?;
+                resolved.insert(key, faces.clone());
+                faces
+            }
+        };
+        Ok(Some(faces.unwrap_or_else(|| self.default.clone())))
+    }
+}
+
+impl Default for BlockTable {
+    fn default() -> Self {
+        BlockTable::new()
+    }
+}
+
+// Bloques de una estructura antes de colocarla: dimensiones, paleta de estados
+// y las celdas ocupadas como (x, y, z, índice de la paleta)
+struct Blocks {
+    dims: [usize; 3],
+    states: Vec<String>,
+    cells: Vec<(usize, usize, usize, usize)>,
+}
+
+fn int(tag: &Tag, key: &str) -> Option<i64> {
+    tag.get(key).and_then(Tag::as_i64)
+}
+
+fn dimension(value: i64) -> Option<usize> {
+    usize::try_from(value).ok().filter(|&v| v > 0 && v <= 4096)
+}
+
+// Estado de bloque como cadena a partir de un compuesto {Name, Properties},
+// como los de las paletas de estructuras y chunks
+pub fn block_state(entry: &Tag) -> Option<String> {
+    let name = entry.get("Name")?.as_str()?;
+    let properties: Vec<String> = entry.get("Properties").and_then(Tag::as_compound)
+        .map(|properties| properties.iter()
+            .filter_map(|(key, value)| Some(format!("{}={}", key, value.as_str()?)))
+            .collect())
+        .unwrap_or_default();
+    if properties.is_empty() {
+        Some(name.to_string())
+    } else {
+        Some(format!("{}[{}]", name, properties.join(",")))
+    }
+}
+
+// Archivo de estructura de Minecraft (.nbt, el que guardan los bloques de
+// estructura): lista de bloques con posición e índice de paleta
+fn structure_blocks(root: &Tag) -> Result<Blocks, String> {
+    let size = root.get("size").and_then(Tag::as_list)
+        .filter(|size| size.len() == 3)
+        .ok_or("structure has no size")?;
+    let mut dims = [0; 3];
+    for (axis, value) in size.iter().enumerate() {
+        dims[axis] = value.as_i64().and_then(dimension).ok_or("invalid structure size")?;
+    }
+
+    // Algunas estructuras guardan varias paletas alternativas; se usa la primera
+    let palette = root.get("palette")
+        .or_else(|| root.get("palettes").and_then(Tag::as_list).and_then(|palettes| palettes.first()))
+        .and_then(Tag::as_list)
+        .ok_or("structure has no palette")?;
+    let states = palette.iter().map(block_state).collect::<Option<Vec<_>>>().ok_or("invalid palette entry")?;
+
+    let mut cells = Vec::new();
+    for block in root.get("blocks").and_then(Tag::as_list).ok_or("structure has no blocks")? {
+        let pos = block.get("pos").and_then(Tag::as_list).filter(|pos| pos.len() == 3).ok_or("block without position")?;
+        let coordinate = |axis: usize| pos[axis].as_i64().and_then(|v| usize::try_from(v).ok()).filter(|&v| v < dims[axis]);
+        let (Some(x), Some(y), Some(z)) = (coordinate(0), coordinate(1), coordinate(2)) else {
+            return Err("block outside the structure size".to_string());
+        };
+        let state = int(block, "state").and_then(|s| usize::try_from(s).ok()).filter(|&s| s < states.len())
+            .ok_or("block with an invalid palette index")?;
+        cells.push((x, y, z, state));
+    }
+
+    Ok(Blocks { dims, states, cells })
+}
+
+// Esquema de Sponge (.schem), versiones 1 a 3: la paleta asocia cada estado a
+// un índice y los datos son esos índices como varints en orden x, z, y
+fn sponge_blocks(root: &Tag) -> Result<Blocks, String> {
+    // La versión 3 anida todo dentro de "Schematic" y los bloques en "Blocks"
+    let root = root.get("Schematic").unwrap_or(root);
+    let blocks = root.get("Blocks").unwrap_or(root);
+    let width = int(root, "Width").and_then(|v| dimension(v & 0xffff)).ok_or("schematic has no width")?;
+    let height = int(root, "Height").and_then(|v| dimension(v & 0xffff)).ok_or("schematic has no height")?;
+    let length = int(root, "Length").and_then(|v| dimension(v & 0xffff)).ok_or("schematic has no length")?;
+
+    let palette = blocks.get("Palette").and_then(Tag::as_compound).ok_or("schematic has no palette")?;
+    let mut states = vec![String::new(); palette.len()];
+    for (state, index) in palette {
+        let index = index.as_i64().and_then(|i| usize::try_from(i).ok()).filter(|&i| i < states.len())
+            .ok_or("invalid palette index")?;
+        states[index] = state.clone();
+    }
+
+    let data = match blocks.get("BlockData").or_else(|| blocks.get("Data")) {
+        Some(Tag::ByteArray(data)) => data,
+        _ => return Err("schematic has no block data".to_string()),
+    };
+
+    let mut cells = Vec::with_capacity(data.len());
+    let mut value = 0usize;
+    let mut shift = 0;
+    for &byte in data {
+        value |= ((byte as u8 & 0x7f) as usize) << shift;
+        if byte as u8 & 0x80 != 0 {
+            shift += 7;
+            if shift > 28 {
+                return Err("invalid varint in block data".to_string());
+            }
+            continue;
+        }
+        let cell = cells.len();
+        if cell >= width * height * length {
+            break;
+        }
+        if value >= states.len() {
+            return Err("block with an invalid palette index".to_string());
+        }
+        cells.push((cell % width, cell / (width * length), (cell / width) % length, value));
+        value = 0;
+        shift = 0;
+    }
+
+    Ok(Blocks { dims: [width, height, length], states, cells })
+}
+
+// Carga una estructura (.nbt) o un esquema de Sponge (.schem) como una rejilla
+// de bloques con su esquina mínima en `origin`. Los ejes de Minecraft (Y hacia
+// arriba) coinciden con los de la escena.
+pub fn load_structure(path: &Path, origin: Vec3, block_size: f32, table: &BlockTable) -> Result<VoxelGrid, ImportError> {
+    let data = std::fs::read(path).map_err(|err| ImportError::new(path, err.to_string()))?;
+    let root = nbt::decompress(&data)
+        .and_then(|data| nbt::parse(&data))
+        .map_err(|err| ImportError::new(path, err))?;
+
+    let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+    let blocks = if extension.eq_ignore_ascii_case("schem") || extension.eq_ignore_ascii_case("schematic") {
+        sponge_blocks(&root)
+    } else {
+        structure_blocks(&root)
+    }
+    .map_err(|err| ImportError::new(path, err))?;
+
+    if cell_count(blocks.dims).is_none() {
+        return Err(ImportError::new(path, "structure is too large"));
+    }
+    if blocks.states.len() >= u16::MAX as usize {
+        return Err(ImportError::new(path, "too many block states"));
+    }
+
+    // Cada estado con material ocupa un hueco de la paleta de la rejilla
+    let mut palette = Vec::new();
+    let mut ids = Vec::with_capacity(blocks.states.len());
+    for state in &blocks.states {
+        ids.push(match table.get(state)? {
+            Some(faces) => {
+                palette.push(faces);
+                palette.len() as u16
+            }
+            None => 0,
+        });
+    }
+
+    let mut grid = VoxelGrid::new(origin, block_size, blocks.dims, palette).map_err(|err| ImportError::new(path, err))?;
+    for (x, y, z, state) in blocks.cells {
+        grid.set(x, y, z, ids[state]);
+    }
+    Ok(grid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compound(entries: Vec<(&str, Tag)>) -> Tag {
+        Tag::Compound(entries.into_iter().map(|(key, value)| (key.to_string(), value)).collect())
+    }
+
+    fn bytes(data: &[u8]) -> Tag {
+        Tag::ByteArray(data.iter().map(|&b| b as i8).collect())
+    }
+
+    // Esquema de 2x2x2 con 200 estados "minecraft:b<i>" en el índice i
+    fn sponge(data: &[u8]) -> Tag {
+        let palette = (0..200).map(|i| (format!("minecraft:b{}", i), Tag::Int(i))).collect();
+        compound(vec![
+            ("Width", Tag::Short(2)),
+            ("Height", Tag::Short(2)),
+            ("Length", Tag::Short(2)),
+            ("Palette", Tag::Compound(palette)),
+            ("BlockData", bytes(data)),
+        ])
+    }
+
+    #[test]
+    fn normalizes_block_state_keys() {
+        assert_eq!(block_state_key("obsidian"), "minecraft:obsidian");
+        assert_eq!(block_state_key("stairs[half=top, facing=east]"), "minecraft:stairs[facing=east,half=top]");
+        assert_eq!(block_state_key("mod:block[]"), "mod:block");
+    }
+
+    #[test]
+    fn decodes_sponge_varints_in_x_z_y_order() {
+        // 150 y 199 necesitan dos bytes de varint
+        let Ok(blocks) = sponge_blocks(&sponge(&[0, 1, 2, 3, 4, 5, 0x96, 0x01, 0xc7, 0x01])) else {
+            panic!("valid schematic was rejected");
+        };
+        assert_eq!(blocks.dims, [2, 2, 2]);
+        assert_eq!(blocks.states[150], "minecraft:b150");
+        assert_eq!(blocks.cells, vec![
+            (0, 0, 0, 0), (1, 0, 0, 1), (0, 0, 1, 2), (1, 0, 1, 3),
+            (0, 1, 0, 4), (1, 1, 0, 5), (0, 1, 1, 150), (1, 1, 1, 199),
+        ]);
+    }
+
+    #[test]
+    fn reads_version_3_schematics() {
+        let palette = compound(vec![("minecraft:air", Tag::Int(0)), ("minecraft:stone", Tag::Int(1))]);
+        let root = compound(vec![("Schematic", compound(vec![
+            ("Width", Tag::Short(3)),
+            ("Height", Tag::Short(1)),
+            ("Length", Tag::Short(1)),
+            ("Blocks", compound(vec![("Palette", palette), ("Data", bytes(&[1, 0, 1]))])),
+        ]))]);
+        let Ok(blocks) = sponge_blocks(&root) else {
+            panic!("valid schematic was rejected");
+        };
+        assert_eq!(blocks.cells, vec![(0, 0, 0, 1), (1, 0, 0, 0), (2, 0, 0, 1)]);
+        assert_eq!(blocks.states[1], "minecraft:stone");
+    }
+
+    #[test]
+    fn rejects_invalid_sponge_data() {
+        assert!(sponge_blocks(&sponge(&[0x80, 0x80, 0x80, 0x80, 0x80, 0x01])).is_err());
+        assert!(sponge_blocks(&sponge(&[0xc8, 0x01])).is_err());
+    }
+
+    #[test]
+    fn reads_structure_blocks() {
+        let state = |name: &str| compound(vec![("Name", Tag::String(name.to_string()))]);
+        let block = |pos: [i32; 3], index: i32| compound(vec![
+            ("pos", Tag::List(pos.iter().map(|&p| Tag::Int(p)).collect())),
+            ("state", Tag::Int(index)),
+        ]);
+        let root = compound(vec![
+            ("size", Tag::List(vec![Tag::Int(2), Tag::Int(3), Tag::Int(4)])),
+            ("palette", Tag::List(vec![state("minecraft:stone"), state("minecraft:dirt")])),
+            ("blocks", Tag::List(vec![block([1, 2, 3], 1), block([0, 0, 0], 0)])),
+        ]);
+        let Ok(blocks) = structure_blocks(&root) else {
+            panic!("valid structure was rejected");
+        };
+        assert_eq!(blocks.dims, [2, 3, 4]);
+        assert_eq!(blocks.cells, vec![(1, 2, 3, 1), (0, 0, 0, 0)]);
+
+        let outside = compound(vec![
+            ("size", Tag::List(vec![Tag::Int(2), Tag::Int(3), Tag::Int(4)])),
+            ("palette", Tag::List(vec![state("minecraft:stone")])),
+            ("blocks", Tag::List(vec![block([2, 0, 0], 0)])),
+        ]);
+        assert!(structure_blocks(&outside).is_err());
+    }
+}
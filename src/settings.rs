@@ -0,0 +1,106 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::ambient_occlusion::{AmbientOcclusion, DEFAULT_AO_RADIUS, DEFAULT_AO_SAMPLES};
+use crate::debug_view::DebugView;
+use crate::filter::PixelFilter;
+use crate::integrator::Integrator;
+use crate::path_tracer::{PathTracer, DEFAULT_MAX_BOUNCES};
+use crate::whitted::{Whitted, DEFAULT_MAX_DEPTH};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntegratorKind {
+    // Trazado recursivo clásico: luces directas, espejo y refracción
+    Whitted,
+    // Trazado de caminos Monte Carlo con iluminación global
+    PathTracer,
+    AmbientOcclusion,
+    Debug(DebugView),
+}
+
+impl IntegratorKind {
+    pub const ALL: [IntegratorKind; 7] = [
+        IntegratorKind::Whitted,
+        IntegratorKind::PathTracer,
+        IntegratorKind::AmbientOcclusion,
+        IntegratorKind::Debug(DebugView::Normals),
+        IntegratorKind::Debug(DebugView::Depth),
+        IntegratorKind::Debug(DebugView::Albedo),
+        IntegratorKind::Debug(DebugView::Uv),
+    ];
+
+    pub fn next(self) -> IntegratorKind {
+        let index = IntegratorKind::ALL.iter().position(|&k| k == self).unwrap_or(0);
+        IntegratorKind::ALL[(index + 1) % IntegratorKind::ALL.len()]
+    }
+}
+
+impl fmt::Display for IntegratorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            IntegratorKind::Whitted => "whitted",
+            IntegratorKind::PathTracer => "path",
+            IntegratorKind::AmbientOcclusion => "ao",
+            IntegratorKind::Debug(DebugView::Normals) => "normals",
+            IntegratorKind::Debug(DebugView::Depth) => "depth",
+            IntegratorKind::Debug(DebugView::Albedo) => "albedo",
+            IntegratorKind::Debug(DebugView::Uv) => "uv",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for IntegratorKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        IntegratorKind::ALL
+            .iter()
+            .copied()
+            .find(|kind| kind.to_string() == s)
+            .ok_or_else(|| {
+                format!("unknown integrator '{}', expected whitted, path, ao, normals, depth, albedo or uv", s)
+            })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RenderSettings {
+    // Hilos de render; 0 usa todos los núcleos
+    pub threads: usize,
+    pub samples_per_pixel: usize,
+    pub filter: PixelFilter,
+    pub integrator: IntegratorKind,
+    // Límite de rebotes; None deja el de cada integrador
+    pub max_depth: Option<u32>,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        RenderSettings {
+            threads: 0,
+            samples_per_pixel: 1,
+            filter: PixelFilter::Box,
+            integrator: IntegratorKind::Whitted,
+            max_depth: None,
+        }
+    }
+}
+
+impl RenderSettings {
+    pub fn thread_count(&self) -> usize {
+        match self.threads {
+            0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+            n => n,
+        }
+    }
+
+    pub fn build_integrator(&self) -> Box<dyn Integrator> {
+        match self.integrator {
+            IntegratorKind::Whitted => Box::new(Whitted::new(self.max_depth.unwrap_or(DEFAULT_MAX_DEPTH))),
+            IntegratorKind::PathTracer => Box::new(PathTracer::new(self.max_depth.unwrap_or(DEFAULT_MAX_BOUNCES))),
+            IntegratorKind::AmbientOcclusion => Box::new(AmbientOcclusion::new(DEFAULT_AO_SAMPLES, DEFAULT_AO_RADIUS)),
+            IntegratorKind::Debug(view) => Box::new(view),
+        }
+    }
+}
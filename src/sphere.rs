@@ -0,0 +1,64 @@
+use nalgebra_glm::Vec3;
+use std::f32::consts::PI;
+use std::sync::Arc;
+use crate::aabb::Aabb;
+use crate::material::Material;
+use crate::ray_intersect::{RayIntersect, Intersect};
+
+pub struct Sphere {
+    pub center: Vec3,
+    pub radius: f32,
+    pub material: Arc<Material>,
+}
+
+impl Sphere {
+    pub fn new(center: Vec3, radius: f32, material: Arc<Material>) -> Self {
+        Sphere { center, radius, material }
+    }
+}
+
+impl RayIntersect for Sphere {
+    fn ray_intersect(&self, ray_origin: &Vec3, ray_direction: &Vec3) -> Intersect {
+        let oc = ray_origin - self.center;
+        let a = ray_direction.dot(ray_direction);
+        let half_b = oc.dot(ray_direction);
+        let c = oc.dot(&oc) - self.radius * self.radius;
+        let discriminant = half_b * half_b - a * c;
+
+        if discriminant < 0.0 {
+            return Intersect::empty();
+        }
+
+        // La raíz más cercana delante del origen; si el origen está dentro de
+        // la esfera es la de salida
+        let root = discriminant.sqrt();
+        let mut t = (-half_b - root) / a;
+        if t < 0.0 {
+            t = (-half_b + root) / a;
+        }
+        if t < 0.0 {
+            return Intersect::empty();
+        }
+
+        let point = ray_origin + ray_direction * t;
+        let normal = (point - self.center) / self.radius;
+
+        // Longitud alrededor del eje Y y latitud desde el polo norte
+        let u = 0.5 + normal.z.atan2(-normal.x) / (2.0 * PI);
+        let v = normal.y.clamp(-1.0, 1.0).acos() / PI;
+
+        Intersect::new(point, normal, t, Arc::clone(&self.material), (u, v)).with_uv_density(1.0 / (PI * self.radius))
+    }
+
+    fn bounds(&self) -> Aabb {
+        let extent = Vec3::repeat(self.radius.abs());
+        Aabb::new(self.center - extent, self.center + extent)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if !(self.radius > 0.0 && self.radius.is_finite()) {
+            return Err(format!("sphere radius must be positive and finite, got {}", self.radius));
+        }
+        Ok(())
+    }
+}
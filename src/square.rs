@@ -1,15 +1,30 @@
 use nalgebra_glm::Vec3;
-use std::rc::Rc;
+use std::sync::Arc;
 use crate::material::Material;
+use crate::aabb::Aabb;
 use crate::ray_intersect::{RayIntersect, Intersect}; // Asegúrate de que Intersect esté definido aquí
+use crate::voxel_grid::DEFAULT_BLOCK_SIZE;
 
+// Cuadrado horizontal mirando hacia arriba. La textura se repite cada `tile`
+// unidades empezando en la esquina mínima, orientada como la cara superior
+// de un bloque.
 pub struct Square {  
     pub center: Vec3,
     pub size: f32, 
-    pub material: Rc<Material>,
+    pub material: Arc<Material>,
+    pub tile: f32,
 }
 
 impl Square {
+    pub fn new(center: Vec3, size: f32, material: Arc<Material>) -> Self {
+        Square { center, size, material, tile: DEFAULT_BLOCK_SIZE }
+    }
+
+    pub fn with_tile(mut self, tile: f32) -> Self {
+        self.tile = tile;
+        self
+    }
+
     fn is_point_inside(&self, point: &Vec3) -> bool {
         let half_size = self.size / 2.0;
         (point.x >= self.center.x - half_size && point.x <= self.center.x + half_size) &&
@@ -24,31 +39,42 @@ impl RayIntersect for Square {
         let d = -self.center.y;
 
         // Calcula el punto de intersección del rayo con el plano
-        let denom = nalgebra_glm::dot(&normal, &ray_direction);
+        let denom = nalgebra_glm::dot(&normal, ray_direction);
         if denom.abs() > 1e-6 {
-            let t = -(nalgebra_glm::dot(&normal, &ray_origin) + d) / denom;
+            let t = -(nalgebra_glm::dot(&normal, ray_origin) + d) / denom;
             if t >= 0.0 {
                 let hit_point = ray_origin + ray_direction * t;
 
 
                 if self.is_point_inside(&hit_point) {
-                    return Intersect { 
-                        point: hit_point,
-                        normal,
-                        distance: t,
-                        material: Rc::clone(&self.material), 
-                        is_intersecting: true, 
-                    };
+                    let half_size = self.size / 2.0;
+                    let u = (hit_point.x - self.center.x + half_size) / self.tile;
+                    let v = (hit_point.z - self.center.z + half_size) / self.tile;
+                    let uv = (u.rem_euclid(1.0), v.rem_euclid(1.0));
+                    return Intersect::new(hit_point, normal, t, Arc::clone(&self.material), uv)
+                        .with_uv_density(1.0 / self.tile);
                 }
             }
         }
         
-        Intersect {
-            point: Vec3::new(0.0, 0.0, 0.0), 
-            normal: Vec3::new(0.0, 0.0, 0.0),
-            distance: f32::INFINITY,
-            material: Rc::clone(&self.material), 
-            is_intersecting: false, 
+        Intersect::empty()
+    }
+
+    fn bounds(&self) -> Aabb {
+        let half_size = self.size / 2.0;
+        Aabb::new(
+            Vec3::new(self.center.x - half_size, self.center.y, self.center.z - half_size),
+            Vec3::new(self.center.x + half_size, self.center.y, self.center.z + half_size),
+        )
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if !(self.size > 0.0 && self.size.is_finite()) {
+            return Err(format!("square size must be positive and finite, got {}", self.size));
+        }
+        if !(self.tile > 0.0 && self.tile.is_finite()) {
+            return Err(format!("square tile must be positive and finite, got {}", self.tile));
         }
+        Ok(())
     }
 }
@@ -1,29 +1,251 @@
-use image::{DynamicImage, GenericImageView};
+use image::{DynamicImage, ImageResult};
 use crate::color::Color;
-use std::rc::Rc;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
 
+// Cómo se leen los texeles: el más cercano (para texturas de píxeles como las
+// de los bloques), interpolación bilineal, o bilineal entre dos niveles de la
+// cadena de mips elegidos según el tamaño del píxel sobre la superficie
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterMode {
+    Nearest,
+    Bilinear,
+    Trilinear,
+}
+
+// Qué pasa con las coordenadas fuera de [0, 1]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WrapMode {
+    Repeat,
+    Mirror,
+    Clamp,
+}
+
+impl fmt::Display for FilterMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            FilterMode::Nearest => "nearest",
+            FilterMode::Bilinear => "bilinear",
+            FilterMode::Trilinear => "trilinear",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for FilterMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [FilterMode::Nearest, FilterMode::Bilinear, FilterMode::Trilinear]
+            .into_iter()
+            .find(|mode| mode.to_string() == s)
+            .ok_or_else(|| format!("unknown texture filter '{}', expected nearest, bilinear or trilinear", s))
+    }
+}
+
+impl fmt::Display for WrapMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            WrapMode::Repeat => "repeat",
+            WrapMode::Mirror => "mirror",
+            WrapMode::Clamp => "clamp",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for WrapMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [WrapMode::Repeat, WrapMode::Mirror, WrapMode::Clamp]
+            .into_iter()
+            .find(|mode| mode.to_string() == s)
+            .ok_or_else(|| format!("unknown texture wrap '{}', expected repeat, mirror or clamp", s))
+    }
+}
+
+impl WrapMode {
+    // Índice de texel dentro de [0, size) para una coordenada entera cualquiera
+    fn apply(self, coordinate: i64, size: usize) -> usize {
+        let size = size as i64;
+        let wrapped = match self {
+            WrapMode::Repeat => coordinate.rem_euclid(size),
+            WrapMode::Mirror => {
+                let period = coordinate.rem_euclid(2 * size);
+                if period < size { period } else { 2 * size - 1 - period }
+            }
+            WrapMode::Clamp => coordinate.clamp(0, size - 1),
+        };
+        wrapped as usize
+    }
+}
+
+// Un nivel de la cadena de mips, ya en color lineal
+#[derive(Debug)]
+struct MipLevel {
+    width: usize,
+    height: usize,
+    texels: Vec<Color>,
+}
+
+impl MipLevel {
+    // Mitad de tamaño promediando bloques de 2x2 texeles
+    fn downsample(&self) -> MipLevel {
+        let width = (self.width / 2).max(1);
+        let height = (self.height / 2).max(1);
+        let mut texels = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                let mut sum = Color::black();
+                for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                    let sx = (2 * x + dx).min(self.width - 1);
+                    let sy = (2 * y + dy).min(self.height - 1);
+                    sum = sum + self.texels[sy * self.width + sx];
+                }
+                texels.push(sum * 0.25);
+            }
+        }
+        MipLevel { width, height, texels }
+    }
+}
+
+// Textura en color lineal. Por defecto se lee el texel más cercano y las
+// coordenadas se limitan a [0, 1], de modo que el borde de una cara de
+// bloque no toma texeles del borde opuesto.
 #[derive(Debug, Clone)]
 pub struct Texture {
-    pub image: Rc<DynamicImage>,
+    pub filter: FilterMode,
+    pub wrap: WrapMode,
+    // Niveles de mip, del tamaño original hasta 1x1
+    levels: Arc<Vec<MipLevel>>,
+    // Texeles recortados (alfa menor que la mitad) del nivel 0; vacío si la
+    // imagen no tiene canal alfa
+    cutout: Arc<Vec<bool>>,
 }
 
 impl Texture {
-    pub fn new(path: &str) -> Self {
-        let image = Rc::new(image::open(path).expect("Failed to load texture"));
-        Texture { image }
+    pub fn load<P: AsRef<Path>>(path: P) -> ImageResult<Self> {
+        Ok(Texture::from_image(image::open(path)?))
     }
 
-    pub fn get_color(&self, u: f32, v: f32) -> Color {
-        let width = self.image.width();
-        let height = self.image.height();
-        let x = (u * width as f32).clamp(0.0, width as f32 - 1.0) as u32;
-        let y = (v * height as f32).clamp(0.0, height as f32 - 1.0) as u32;
+    pub fn from_image(image: DynamicImage) -> Self {
+        let (width, height) = (image.width() as usize, image.height() as usize);
+        let texels = image.to_rgb8().pixels().map(|p| Color::from_srgb8(p[0], p[1], p[2])).collect();
+        let cutout = if image.color().has_alpha() {
+            image.to_rgba8().pixels().map(|p| p[3] < 128).collect()
+        } else {
+            Vec::new()
+        };
+        let mut levels = vec![MipLevel { width, height, texels }];
+        while let Some(last) = levels.last().filter(|l| l.width * l.height > 1) {
+            let next = last.downsample();
+            levels.push(next);
+        }
+
+        Texture {
+            filter: FilterMode::Nearest,
+            wrap: WrapMode::Clamp,
+            levels: Arc::new(levels),
+            cutout: Arc::new(cutout),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.levels[0].width
+    }
+
+    pub fn height(&self) -> usize {
+        self.levels[0].height
+    }
+
+    pub fn with_sampling(mut self, filter: FilterMode, wrap: WrapMode) -> Self {
+        self.filter = filter;
+        self.wrap = wrap;
+        self
+    }
+
+    // Copia con cada texel multiplicado por `tint`, en todos los niveles
+    pub fn tinted(&self, tint: Color) -> Self {
+        let levels = self.levels.iter()
+            .map(|level| MipLevel {
+                width: level.width,
+                height: level.height,
+                texels: level.texels.iter().map(|&texel| texel * tint).collect(),
+            })
+            .collect();
+        Texture { levels: Arc::new(levels), ..self.clone() }
+    }
 
-        let pixel = self.image.get_pixel(x, y);
-        Color::new(pixel[0], pixel[1], pixel[2])
+    fn texel(&self, level: &MipLevel, x: i64, y: i64) -> Color {
+        let x = self.wrap.apply(x, level.width);
+        let y = self.wrap.apply(y, level.height);
+        level.texels[y * level.width + x]
     }
 
-    pub fn sample(&self, uv: (f32, f32)) -> Color {
-        self.get_color(uv.0, uv.1)
+    fn nearest(&self, level: &MipLevel, u: f32, v: f32) -> Color {
+        let x = (u * level.width as f32).floor() as i64;
+        let y = (v * level.height as f32).floor() as i64;
+        self.texel(level, x, y)
+    }
+
+    fn bilinear(&self, level: &MipLevel, u: f32, v: f32) -> Color {
+        // Los centros de los texeles están en medio de cada celda
+        let x = u * level.width as f32 - 0.5;
+        let y = v * level.height as f32 - 0.5;
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as i64, y0 as i64);
+
+        let top = self.texel(level, x0, y0) * (1.0 - fx) + self.texel(level, x0 + 1, y0) * fx;
+        let bottom = self.texel(level, x0, y0 + 1) * (1.0 - fx) + self.texel(level, x0 + 1, y0 + 1) * fx;
+        top * (1.0 - fy) + bottom * fy
+    }
+
+    // Si el texel más cercano a `uv` está recortado. Las hojas, el cristal o
+    // las flores dejan ver lo que hay detrás a través de esos texeles.
+    pub fn is_cutout(&self, uv: (f32, f32)) -> bool {
+        let base = &self.levels[0];
+        if self.cutout.is_empty() || !(uv.0.is_finite() && uv.1.is_finite()) {
+            return false;
+        }
+        let x = self.wrap.apply((uv.0 * base.width as f32).floor() as i64, base.width);
+        let y = self.wrap.apply((uv.1 * base.height as f32).floor() as i64, base.height);
+        self.cutout[y * base.width + x]
+    }
+
+    pub fn get_color(&self, u: f32, v: f32) -> Color {
+        self.sample((u, v), 0.0)
+    }
+
+    // Color en `uv`. `footprint` es el ancho del píxel sobre la superficie en
+    // unidades de UV; sólo el filtro trilineal lo usa para elegir el mip.
+    pub fn sample(&self, uv: (f32, f32), footprint: f32) -> Color {
+        let base = &self.levels[0];
+        if base.texels.is_empty() || !(uv.0.is_finite() && uv.1.is_finite()) {
+            return Color::black();
+        }
+
+        match self.filter {
+            FilterMode::Nearest => self.nearest(base, uv.0, uv.1),
+            FilterMode::Bilinear => self.bilinear(base, uv.0, uv.1),
+            FilterMode::Trilinear => {
+                let texels = footprint * base.width.max(base.height) as f32;
+                let last = (self.levels.len() - 1) as f32;
+                let lod = if texels > 1.0 { texels.log2().min(last) } else { 0.0 };
+
+                let lower = lod.floor();
+                let fraction = lod - lower;
+                let color = self.bilinear(&self.levels[lower as usize], uv.0, uv.1);
+                if fraction > 0.0 {
+                    let upper = self.bilinear(&self.levels[lower as usize + 1], uv.0, uv.1);
+                    color * (1.0 - fraction) + upper * fraction
+                } else {
+                    color
+                }
+            }
+        }
     }
 }
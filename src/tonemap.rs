@@ -0,0 +1,117 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::color::Color;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToneMapper {
+    Clamp,
+    Reinhard,
+    AcesFilmic,
+    Uncharted2,
+}
+
+impl ToneMapper {
+    pub const ALL: [ToneMapper; 4] = [
+        ToneMapper::Clamp,
+        ToneMapper::Reinhard,
+        ToneMapper::AcesFilmic,
+        ToneMapper::Uncharted2,
+    ];
+
+    pub fn next(self) -> ToneMapper {
+        let index = ToneMapper::ALL.iter().position(|&m| m == self).unwrap_or(0);
+        ToneMapper::ALL[(index + 1) % ToneMapper::ALL.len()]
+    }
+
+    fn map(self, x: f32) -> f32 {
+        match self {
+            ToneMapper::Clamp => x,
+            ToneMapper::Reinhard => x / (1.0 + x),
+            // Aproximación de Krzysztof Narkowicz a la curva ACES
+            ToneMapper::AcesFilmic => (x * (2.51 * x + 0.03)) / (x * (2.43 * x + 0.59) + 0.14),
+            // Curva filmica de John Hable, normalizada al punto blanco W = 11.2
+            ToneMapper::Uncharted2 => uncharted2(2.0 * x) / uncharted2(11.2),
+        }
+    }
+}
+
+fn uncharted2(x: f32) -> f32 {
+    const A: f32 = 0.15;
+    const B: f32 = 0.50;
+    const C: f32 = 0.10;
+    const D: f32 = 0.20;
+    const E: f32 = 0.02;
+    const F: f32 = 0.30;
+    ((x * (A * x + C * B) + D * E) / (x * (A * x + B) + D * F)) - E / F
+}
+
+impl fmt::Display for ToneMapper {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            ToneMapper::Clamp => "clamp",
+            ToneMapper::Reinhard => "reinhard",
+            ToneMapper::AcesFilmic => "aces",
+            ToneMapper::Uncharted2 => "uncharted2",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for ToneMapper {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ToneMapper::ALL
+            .iter()
+            .copied()
+            .find(|mapper| mapper.to_string() == s)
+            .ok_or_else(|| format!("unknown tone mapper '{}', expected clamp, reinhard, aces or uncharted2", s))
+    }
+}
+
+pub fn linear_to_srgb(c: f32) -> f32 {
+    if c <= 0.0031308 {
+        12.92 * c
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+pub fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+// Convierte la radiancia lineal en el color que se muestra: exposición (en
+// pasos f), curva de tonos y, por último, codificación sRGB.
+#[derive(Debug, Clone, Copy)]
+pub struct OutputTransform {
+    pub exposure: f32,
+    pub tone_mapper: ToneMapper,
+    pub srgb: bool,
+}
+
+impl Default for OutputTransform {
+    fn default() -> Self {
+        OutputTransform {
+            exposure: 0.0,
+            tone_mapper: ToneMapper::Clamp,
+            srgb: true,
+        }
+    }
+}
+
+impl OutputTransform {
+    pub fn apply(&self, radiance: Color) -> u32 {
+        let scale = self.exposure.exp2();
+        let channel = |c: f32| {
+            let mapped = self.tone_mapper.map((c * scale).max(0.0)).clamp(0.0, 1.0);
+            if self.srgb { linear_to_srgb(mapped) } else { mapped }
+        };
+        Color::new(channel(radiance.r), channel(radiance.g), channel(radiance.b)).to_hex()
+    }
+}
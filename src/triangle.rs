@@ -0,0 +1,100 @@
+use nalgebra_glm::Vec3;
+use std::sync::Arc;
+use crate::aabb::Aabb;
+use crate::material::Material;
+use crate::ray_intersect::{RayIntersect, Intersect};
+
+// Triángulo con normales y coordenadas de textura opcionales por vértice. Sin
+// normales se usa la del plano del triángulo; sin UV se usan las coordenadas
+// baricéntricas del impacto.
+pub struct Triangle {
+    pub vertices: [Vec3; 3],
+    pub normals: Option<[Vec3; 3]>,
+    pub uvs: Option<[(f32, f32); 3]>,
+    pub material: Arc<Material>,
+}
+
+impl Triangle {
+    pub fn new(a: Vec3, b: Vec3, c: Vec3, material: Arc<Material>) -> Self {
+        Triangle {
+            vertices: [a, b, c],
+            normals: None,
+            uvs: None,
+            material,
+        }
+    }
+
+    pub fn with_normals(mut self, normals: [Vec3; 3]) -> Self {
+        self.normals = Some(normals);
+        self
+    }
+
+    pub fn with_uvs(mut self, uvs: [(f32, f32); 3]) -> Self {
+        self.uvs = Some(uvs);
+        self
+    }
+}
+
+impl RayIntersect for Triangle {
+    // Möller–Trumbore: resuelve a la vez la distancia y las coordenadas
+    // baricéntricas (u, v) del impacto
+    fn ray_intersect(&self, ray_origin: &Vec3, ray_direction: &Vec3) -> Intersect {
+        let [a, b, c] = self.vertices;
+        let edge1 = b - a;
+        let edge2 = c - a;
+
+        let p = ray_direction.cross(&edge2);
+        let det = edge1.dot(&p);
+        if det.abs() < 1e-9 {
+            return Intersect::empty();
+        }
+        let inv_det = 1.0 / det;
+
+        let s = ray_origin - a;
+        let u = s.dot(&p) * inv_det;
+        if !(0.0..=1.0).contains(&u) {
+            return Intersect::empty();
+        }
+
+        let q = s.cross(&edge1);
+        let v = ray_direction.dot(&q) * inv_det;
+        if v < 0.0 || u + v > 1.0 {
+            return Intersect::empty();
+        }
+
+        let t = edge2.dot(&q) * inv_det;
+        if t < 0.0 {
+            return Intersect::empty();
+        }
+
+        let w = 1.0 - u - v;
+        let point = ray_origin + ray_direction * t;
+        let normal = match self.normals {
+            Some([n0, n1, n2]) => (n0 * w + n1 * u + n2 * v).normalize(),
+            None => edge1.cross(&edge2).normalize(),
+        };
+        // La densidad compara el área del triángulo en UV con su área real
+        let (uv, uv_area) = match self.uvs {
+            Some([t0, t1, t2]) => (
+                (t0.0 * w + t1.0 * u + t2.0 * v, t0.1 * w + t1.1 * u + t2.1 * v),
+                ((t1.0 - t0.0) * (t2.1 - t0.1) - (t2.0 - t0.0) * (t1.1 - t0.1)).abs(),
+            ),
+            None => ((u, v), 1.0),
+        };
+        let density = (uv_area / edge1.cross(&edge2).norm()).sqrt();
+
+        Intersect::new(point, normal, t, Arc::clone(&self.material), uv).with_uv_density(density)
+    }
+
+    fn bounds(&self) -> Aabb {
+        let [a, b, c] = self.vertices;
+        Aabb::new(a.inf(&b).inf(&c), a.sup(&b).sup(&c))
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if !self.vertices.iter().all(|v| v.iter().all(|c| c.is_finite())) {
+            return Err("triangle vertices must be finite".to_string());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,354 @@
+use nalgebra_glm::Vec3;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::color::Color;
+use crate::import::ImportError;
+use crate::material::{BlockFaces, Material};
+use crate::voxel_grid::{VoxelGrid, DEFAULT_BLOCK_SIZE, MAX_CELLS};
+
+// Cómo colocar un modelo de MagicaVoxel en la escena. `origin` es la esquina
+// mínima del conjunto de modelos; `overrides` sustituye el material generado
+// para un índice de la paleta (1 a 255), por ejemplo por un bloque con textura.
+pub struct VoxOptions {
+    pub origin: Vec3,
+    pub block_size: f32,
+    pub overrides: HashMap<u8, Arc<Material>>,
+}
+
+impl Default for VoxOptions {
+    fn default() -> Self {
+        VoxOptions {
+            origin: Vec3::zeros(),
+            block_size: DEFAULT_BLOCK_SIZE,
+            overrides: HashMap::new(),
+        }
+    }
+}
+
+// Tamaño máximo de un modelo en cada eje, el mismo que permite MagicaVoxel
+const MAX_MODEL_SIZE: i32 = 256;
+
+// Nodos que se visitan como mucho al recorrer el grafo de escena
+const MAX_GRAPH_VISITS: usize = 1 << 20;
+
+struct Model {
+    size: [i32; 3],
+    voxels: Vec<[u8; 4]>,
+}
+
+type Dict = HashMap<String, String>;
+
+// Nodos del grafo de escena (nTRN, nGRP, nSHP). Sólo se usa la traslación de
+// los nodos de transformación; las rotaciones se ignoran.
+enum Node {
+    Transform { child: i32, translation: [i32; 3] },
+    Group { children: Vec<i32> },
+    Shape { models: Vec<i32> },
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, count: usize) -> Option<&'a [u8]> {
+        let end = self.position.checked_add(count)?;
+        let bytes = self.data.get(self.position..end)?;
+        self.position = end;
+        Some(bytes)
+    }
+
+    fn i32(&mut self) -> Option<i32> {
+        self.bytes(4).map(|b| i32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn string(&mut self) -> Option<String> {
+        let len = usize::try_from(self.i32()?).ok()?;
+        self.bytes(len).map(|b| String::from_utf8_lossy(b).into_owned())
+    }
+
+    fn dict(&mut self) -> Option<Dict> {
+        let count = self.i32()?;
+        let mut dict = Dict::new();
+        for _ in 0..count.max(0) {
+            let key = self.string()?;
+            let value = self.string()?;
+            dict.insert(key, value);
+        }
+        Some(dict)
+    }
+}
+
+fn number(dict: &Dict, key: &str) -> Option<f32> {
+    dict.get(key).and_then(|value| value.parse().ok())
+}
+
+// Traduce las propiedades de MATL al material de la escena
+fn material(color: [u8; 4], properties: Option<&Dict>) -> Material {
+    let diffuse = Color::from_srgb8(color[0], color[1], color[2]);
+    let mut material = Material::new(diffuse, 10.0, [0.9, 0.1, 0.0, 0.0], 1.0, None, Color::black());
+    let Some(properties) = properties else {
+        return material;
+    };
+
+    let roughness = number(properties, "_rough").unwrap_or(0.5).clamp(0.0, 1.0);
+    let alpha = (roughness * roughness).max(1e-3);
+    material.specular = (2.0 / (alpha * alpha) - 2.0).clamp(1.0, 1000.0);
+
+    match properties.get("_type").map(String::as_str) {
+        Some("_metal") => {
+            let metal = number(properties, "_metal").unwrap_or(1.0).clamp(0.0, 1.0);
+            material.albedo = [1.0 - metal, 0.04 + 0.96 * metal, metal * (1.0 - roughness), 0.0];
+        }
+        Some("_glass") | Some("_blend") => {
+            let transparency = number(properties, "_trans").unwrap_or(1.0).clamp(0.0, 0.9);
+            material.albedo = [1.0 - transparency, 0.5, 0.1, transparency];
+            // MagicaVoxel guarda el índice de refracción menos uno
+            material.refractive_index = 1.0 + number(properties, "_ior").unwrap_or(0.5);
+        }
+        Some("_emit") => {
+            let emit = number(properties, "_emit").unwrap_or(1.0);
+            let flux = number(properties, "_flux").unwrap_or(0.0);
+            material.emissive_color = diffuse * (emit * (1.0 + flux));
+        }
+        _ => {}
+    }
+
+    material
+}
+
+fn translation(frame: &Dict) -> [i32; 3] {
+    let mut translation = [0; 3];
+    if let Some(value) = frame.get("_t") {
+        for (axis, part) in value.split_whitespace().take(3).enumerate() {
+            translation[axis] = part.parse().unwrap_or(0);
+        }
+    }
+    translation
+}
+
+// Suma componente a componente; None si se sale de i32
+fn checked_offset(a: [i32; 3], b: [i32; 3]) -> Option<[i32; 3]> {
+    Some([a[0].checked_add(b[0])?, a[1].checked_add(b[1])?, a[2].checked_add(b[2])?])
+}
+
+// Posición de cada modelo en el espacio de MagicaVoxel (Z hacia arriba),
+// acumulando las traslaciones del grafo desde la raíz
+fn placements(nodes: &HashMap<i32, Node>, models: &[Model]) -> Result<Vec<(usize, [i32; 3])>, String> {
+    let mut placed = Vec::new();
+    if nodes.is_empty() {
+        // Sin grafo de escena, los modelos quedan en el origen
+        for (index, model) in models.iter().enumerate() {
+            placed.push((index, model.size.map(|s| s / 2)));
+        }
+        return Ok(placed);
+    }
+
+    // Recorrido en profundidad. `path` guarda los nodos entre la raíz y el
+    // actual para detectar ciclos. Como un grupo puede apuntar varias veces al
+    // mismo hijo, también se limitan las visitas y las celdas colocadas.
+    enum Step {
+        Enter(i32, [i32; 3]),
+        Leave(i32),
+    }
+    let mut path = HashSet::new();
+    let mut visits = 0;
+    let mut cells = 0;
+
+    let mut pending = vec![Step::Enter(0, [0; 3])];
+    while let Some(step) = pending.pop() {
+        let (id, offset) = match step {
+            Step::Enter(id, offset) => (id, offset),
+            Step::Leave(id) => {
+                path.remove(&id);
+                continue;
+            }
+        };
+        visits += 1;
+        if visits > MAX_GRAPH_VISITS {
+            return Err("scene graph is too large".to_string());
+        }
+        if !path.insert(id) {
+            return Err(format!("scene graph has a cycle through node {}", id));
+        }
+        pending.push(Step::Leave(id));
+
+        match nodes.get(&id) {
+            Some(Node::Transform { child, translation }) => {
+                let offset = checked_offset(offset, *translation)
+                    .ok_or_else(|| format!("translation of node {} is out of range", id))?;
+                pending.push(Step::Enter(*child, offset));
+            }
+            Some(Node::Group { children }) => {
+                pending.extend(children.iter().map(|&child| Step::Enter(child, offset)));
+            }
+            Some(Node::Shape { models: shape_models }) => {
+                for &model in shape_models {
+                    if let Some(index) = usize::try_from(model).ok().filter(|&m| m < models.len()) {
+                        cells += models[index].size.iter().map(|&s| s as usize).product::<usize>();
+                        if cells > MAX_CELLS {
+                            return Err("scene graph places too many models".to_string());
+                        }
+                        placed.push((index, offset));
+                    }
+                }
+            }
+            None => {}
+        }
+    }
+    Ok(placed)
+}
+
+// Esquina mínima de un modelo en bloques a partir de su centro, ya con Y hacia
+// arriba
+fn model_corner(center: [i32; 3], size: [i32; 3]) -> Option<[i32; 3]> {
+    let [sx, sy, sz] = size;
+    let min = [center[0].checked_sub(sx / 2)?, center[1].checked_sub(sy / 2)?, center[2].checked_sub(sz / 2)?];
+    Some([min[0], min[2], min[1].checked_add(sy)?.checked_neg()?])
+}
+
+// Carga un .vox como una rejilla de bloques por modelo. El eje Z de
+// MagicaVoxel pasa a ser el eje Y de la escena.
+pub fn load_vox(path: &Path, options: &VoxOptions) -> Result<Vec<VoxelGrid>, ImportError> {
+    let data = std::fs::read(path).map_err(|err| ImportError::new(path, err.to_string()))?;
+    let truncated = || ImportError::new(path, "truncated file");
+    let mut reader = Reader { data: &data, position: 0 };
+
+    if reader.bytes(4) != Some(b"VOX ".as_slice()) {
+        return Err(ImportError::new(path, "not a MagicaVoxel file"));
+    }
+    reader.i32().ok_or_else(truncated)?;
+
+    let mut models = Vec::new();
+    let mut size = None;
+    // Los archivos sin paleta propia usan grises
+    let mut palette: Vec<[u8; 4]> = (1..=255).map(|i| [i as u8, i as u8, i as u8, 255]).collect();
+    let mut materials: HashMap<u8, Dict> = HashMap::new();
+    let mut nodes = HashMap::new();
+
+    while reader.position < data.len() {
+        let id = reader.bytes(4).ok_or_else(truncated)?;
+        let content_size = usize::try_from(reader.i32().ok_or_else(truncated)?).map_err(|_| truncated())?;
+        reader.i32().ok_or_else(truncated)?;
+        // MAIN sólo agrupa a los demás fragmentos, que van a continuación
+        if id == b"MAIN" {
+            continue;
+        }
+        let content = reader.bytes(content_size).ok_or_else(truncated)?;
+        let mut chunk = Reader { data: content, position: 0 };
+
+        match id {
+            b"SIZE" => {
+                let x = chunk.i32().ok_or_else(truncated)?;
+                let y = chunk.i32().ok_or_else(truncated)?;
+                let z = chunk.i32().ok_or_else(truncated)?;
+                let cells = [x, y, z].iter().map(|&s| s as i64).product::<i64>();
+                if [x, y, z].iter().any(|&s| s <= 0 || s > MAX_MODEL_SIZE) || cells > MAX_CELLS as i64 {
+                    return Err(ImportError::new(path, format!("invalid model size {}x{}x{}", x, y, z)));
+                }
+                size = Some([x, y, z]);
+            }
+            b"XYZI" => {
+                let size = size.take().ok_or_else(|| ImportError::new(path, "XYZI chunk without SIZE"))?;
+                let count = usize::try_from(chunk.i32().ok_or_else(truncated)?).map_err(|_| truncated())?;
+                let voxels = chunk.bytes(count.checked_mul(4).ok_or_else(truncated)?).ok_or_else(truncated)?;
+                let voxels = voxels.chunks_exact(4).map(|v| [v[0], v[1], v[2], v[3]]).collect();
+                models.push(Model { size, voxels });
+            }
+            b"RGBA" => {
+                let colors = chunk.bytes(256 * 4).ok_or_else(truncated)?;
+                palette = colors.chunks_exact(4).take(255).map(|c| [c[0], c[1], c[2], c[3]]).collect();
+            }
+            b"MATL" => {
+                let index = chunk.i32().ok_or_else(truncated)?;
+                let properties = chunk.dict().ok_or_else(truncated)?;
+                if let Ok(index) = u8::try_from(index) {
+                    materials.insert(index, properties);
+                }
+            }
+            b"nTRN" => {
+                let id = chunk.i32().ok_or_else(truncated)?;
+                chunk.dict().ok_or_else(truncated)?;
+                let child = chunk.i32().ok_or_else(truncated)?;
+                chunk.bytes(8).ok_or_else(truncated)?;
+                let frames = chunk.i32().ok_or_else(truncated)?;
+                let first_frame = if frames > 0 { chunk.dict().ok_or_else(truncated)? } else { Dict::new() };
+                nodes.insert(id, Node::Transform { child, translation: translation(&first_frame) });
+            }
+            b"nGRP" => {
+                let id = chunk.i32().ok_or_else(truncated)?;
+                chunk.dict().ok_or_else(truncated)?;
+                let count = chunk.i32().ok_or_else(truncated)?;
+                let children = (0..count.max(0)).map(|_| chunk.i32()).collect::<Option<Vec<_>>>().ok_or_else(truncated)?;
+                nodes.insert(id, Node::Group { children });
+            }
+            b"nSHP" => {
+                let id = chunk.i32().ok_or_else(truncated)?;
+                chunk.dict().ok_or_else(truncated)?;
+                let count = chunk.i32().ok_or_else(truncated)?;
+                let mut shape_models = Vec::new();
+                for _ in 0..count.max(0) {
+                    shape_models.push(chunk.i32().ok_or_else(truncated)?);
+                    chunk.dict().ok_or_else(truncated)?;
+                }
+                nodes.insert(id, Node::Shape { models: shape_models });
+            }
+            _ => {}
+        }
+    }
+
+    if models.is_empty() {
+        return Err(ImportError::new(path, "file has no models"));
+    }
+
+    // El índice de color i usa la entrada i - 1 de RGBA, igual que los ids de
+    // VoxelGrid, así que los índices pasan tal cual
+    let grid_palette: Vec<BlockFaces> = palette
+        .iter()
+        .enumerate()
+        .map(|(i, &color)| {
+            let index = i as u8 + 1;
+            let material = options.overrides.get(&index).cloned()
+                .unwrap_or_else(|| Arc::new(material(color, materials.get(&index))));
+            BlockFaces::uniform(material)
+        })
+        .collect();
+
+    let out_of_range = || ImportError::new(path, "model position is out of range");
+    let placed = placements(&nodes, &models)
+        .map_err(|message| ImportError::new(path, message))?
+        .into_iter()
+        .map(|(index, center)| Some((index, model_corner(center, models[index].size)?)))
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(out_of_range)?;
+    let lowest = placed.iter().fold([i32::MAX; 3], |lowest, (_, corner)| {
+        [lowest[0].min(corner[0]), lowest[1].min(corner[1]), lowest[2].min(corner[2])]
+    });
+
+    let mut grids = Vec::new();
+    for (index, corner) in placed {
+        let model = &models[index];
+        let [sx, sy, sz] = model.size.map(|s| s as usize);
+        let [x, y, z] = [0, 1, 2].map(|axis| corner[axis].checked_sub(lowest[axis]));
+        let (Some(x), Some(y), Some(z)) = (x, y, z) else {
+            return Err(out_of_range());
+        };
+        let offset = Vec3::new(x as f32, y as f32, z as f32);
+
+        let mut grid = VoxelGrid::new(options.origin + offset * options.block_size, options.block_size, [sx, sz, sy], grid_palette.clone())
+            .map_err(|err| ImportError::new(path, err))?;
+        for &[x, y, z, color] in &model.voxels {
+            let (x, y, z) = (x as usize, y as usize, z as usize);
+            if color == 0 || x >= sx || y >= sy || z >= sz {
+                continue;
+            }
+            grid.set(x, z, sy - 1 - y, color as u16);
+        }
+        grids.push(grid);
+    }
+
+    Ok(grids)
+}
@@ -0,0 +1,220 @@
+use nalgebra_glm::Vec3;
+use std::sync::Arc;
+
+use crate::aabb::Aabb;
+use crate::material::{BlockFaces, Material};
+use crate::ray_intersect::{Intersect, RayIntersect};
+
+pub const EMPTY_BLOCK: u16 = 0;
+// Lado de los bloques importados, el mismo que el de los cubos de la diorama
+pub const DEFAULT_BLOCK_SIZE: f32 = 0.4;
+// Límite de celdas de una rejilla para no reservar memoria sin control
+pub const MAX_CELLS: usize = 1 << 28;
+
+// Número de celdas de una rejilla de `dims`, o None si pasa de MAX_CELLS
+pub fn cell_count(dims: [usize; 3]) -> Option<usize> {
+    dims[0].checked_mul(dims[1])?.checked_mul(dims[2]).filter(|&cells| cells <= MAX_CELLS)
+}
+
+// Rejilla regular de bloques. Cada celda guarda un id: 0 es aire y cualquier
+// otro valor `n` usa los materiales de `palette[n - 1]`. Los rayos la recorren
+// celda por celda con el algoritmo de Amanatides y Woo, así que el costo
+// depende del número de celdas atravesadas y no del número de bloques.
+pub struct VoxelGrid {
+    pub origin: Vec3,
+    pub block_size: f32,
+    pub dims: [usize; 3],
+    pub palette: Vec<BlockFaces>,
+    blocks: Vec<u16>,
+}
+
+impl VoxelGrid {
+    // Todos los importadores pasan por aquí, así que los límites de tamaño se
+    // comprueban una sola vez
+    pub fn new(origin: Vec3, block_size: f32, dims: [usize; 3], palette: Vec<BlockFaces>) -> Result<Self, String> {
+        if !(block_size > 0.0 && block_size.is_finite()) {
+            return Err(format!("block size must be positive, got {}", block_size));
+        }
+        let cells = cell_count(dims).ok_or_else(|| {
+            format!("a {}x{}x{} grid is larger than {} cells", dims[0], dims[1], dims[2], MAX_CELLS)
+        })?;
+        Ok(VoxelGrid {
+            origin,
+            block_size,
+            dims,
+            palette,
+            blocks: vec![EMPTY_BLOCK; cells],
+        })
+    }
+
+    fn index(&self, x: usize, y: usize, z: usize) -> usize {
+        (z * self.dims[1] + y) * self.dims[0] + x
+    }
+
+    pub fn get(&self, x: usize, y: usize, z: usize) -> u16 {
+        if x < self.dims[0] && y < self.dims[1] && z < self.dims[2] {
+            self.blocks[self.index(x, y, z)]
+        } else {
+            EMPTY_BLOCK
+        }
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, z: usize, block: u16) {
+        assert!(
+            (block as usize) <= self.palette.len(),
+            "block id {} is outside a palette of {} materials", block, self.palette.len()
+        );
+        let index = self.index(x, y, z);
+        self.blocks[index] = block;
+    }
+}
+
+// UV dentro de una cara de bloque. `local` es la posición del impacto dentro del
+// bloque en [0, 1]^3; u crece hacia la derecha y v hacia abajo vistas desde fuera.
+pub fn block_face_uv(normal: &Vec3, local: &Vec3) -> (f32, f32) {
+    let (u, v) = if normal.x > 0.5 {
+        (1.0 - local.z, 1.0 - local.y)
+    } else if normal.x < -0.5 {
+        (local.z, 1.0 - local.y)
+    } else if normal.y > 0.5 {
+        (local.x, local.z)
+    } else if normal.y < -0.5 {
+        (local.x, 1.0 - local.z)
+    } else if normal.z > 0.5 {
+        (local.x, 1.0 - local.y)
+    } else {
+        (1.0 - local.x, 1.0 - local.y)
+    };
+
+    (u.clamp(0.0, 1.0), v.clamp(0.0, 1.0))
+}
+
+impl VoxelGrid {
+    // Impacto del rayo en la cara `normal` de la celda `cell` a distancia `t`,
+    // o None si la textura está recortada en ese punto
+    fn face_hit(
+        &self,
+        ray_origin: &Vec3,
+        ray_direction: &Vec3,
+        t: f32,
+        cell: [i64; 3],
+        normal: Vec3,
+        material: &Arc<Material>,
+    ) -> Option<Intersect> {
+        let point = ray_origin + ray_direction * t;
+        let cell_min = Vec3::new(cell[0] as f32, cell[1] as f32, cell[2] as f32);
+        let local = (point - self.origin) / self.block_size - cell_min;
+        let uv = block_face_uv(&normal, &local);
+        if material.is_cutout(uv) {
+            return None;
+        }
+        Some(Intersect::new(point, normal, t, Arc::clone(material), uv).with_uv_density(1.0 / self.block_size))
+    }
+}
+
+impl RayIntersect for VoxelGrid {
+    fn ray_intersect(&self, ray_origin: &Vec3, ray_direction: &Vec3) -> Intersect {
+        if self.blocks.is_empty() {
+            return Intersect::empty();
+        }
+
+        let bounds = self.bounds();
+
+        // Entrada a la rejilla y eje de la cara por la que entra el rayo
+        let mut t_enter = 0.0f32;
+        let mut t_exit = f32::INFINITY;
+        let mut enter_axis = None;
+        for axis in 0..3 {
+            if ray_direction[axis] == 0.0 {
+                if ray_origin[axis] < bounds.min[axis] || ray_origin[axis] > bounds.max[axis] {
+                    return Intersect::empty();
+                }
+                continue;
+            }
+            let t0 = (bounds.min[axis] - ray_origin[axis]) / ray_direction[axis];
+            let t1 = (bounds.max[axis] - ray_origin[axis]) / ray_direction[axis];
+            let (near, far) = if t0 < t1 { (t0, t1) } else { (t1, t0) };
+            if near > t_enter {
+                t_enter = near;
+                enter_axis = Some(axis);
+            }
+            t_exit = t_exit.min(far);
+        }
+        if t_enter > t_exit {
+            return Intersect::empty();
+        }
+
+        let entry = (ray_origin + ray_direction * t_enter - self.origin) / self.block_size;
+        let mut cell = [0i64; 3];
+        let mut step = [0i64; 3];
+        let mut t_max = [f32::INFINITY; 3];
+        let mut t_delta = [f32::INFINITY; 3];
+
+        for axis in 0..3 {
+            let last = self.dims[axis] as i64 - 1;
+            cell[axis] = (entry[axis].floor() as i64).clamp(0, last);
+            if ray_direction[axis] > 0.0 {
+                step[axis] = 1;
+            } else if ray_direction[axis] < 0.0 {
+                step[axis] = -1;
+            } else {
+                continue;
+            }
+            let boundary = self.origin[axis]
+                + (cell[axis] + i64::from(step[axis] > 0)) as f32 * self.block_size;
+            t_max[axis] = (boundary - ray_origin[axis]) / ray_direction[axis];
+            t_delta[axis] = self.block_size / ray_direction[axis].abs();
+        }
+
+        // Un rayo que nace dentro de un bloque no lo golpea, igual que con Cube
+        let mut t = t_enter;
+        let mut hit_axis = enter_axis;
+
+        loop {
+            let axis = if t_max[0] < t_max[1] {
+                if t_max[0] < t_max[2] { 0 } else { 2 }
+            } else if t_max[1] < t_max[2] {
+                1
+            } else {
+                2
+            };
+
+            let block = self.get(cell[0] as usize, cell[1] as usize, cell[2] as usize);
+            if block != EMPTY_BLOCK {
+                if let Some(entry_axis) = hit_axis {
+                    let faces = &self.palette[block as usize - 1];
+                    let mut normal = Vec3::zeros();
+                    normal[entry_axis] = -step[entry_axis] as f32;
+                    if let Some(hit) = self.face_hit(ray_origin, ray_direction, t, cell, normal, faces.facing(&normal)) {
+                        return hit;
+                    }
+
+                    // Por un texel recortado se ve la cara del fondo del mismo
+                    // bloque, por dentro
+                    let mut back = Vec3::zeros();
+                    back[axis] = step[axis] as f32;
+                    if let Some(hit) = self.face_hit(ray_origin, ray_direction, t_max[axis], cell, back, faces.facing(&back)) {
+                        return Intersect { normal: -back, ..hit };
+                    }
+                }
+            }
+
+            if t_max[axis] > t_exit {
+                return Intersect::empty();
+            }
+
+            t = t_max[axis];
+            cell[axis] += step[axis];
+            if cell[axis] < 0 || cell[axis] >= self.dims[axis] as i64 {
+                return Intersect::empty();
+            }
+            t_max[axis] += t_delta[axis];
+            hit_axis = Some(axis);
+        }
+    }
+
+    fn bounds(&self) -> Aabb {
+        let size = Vec3::new(self.dims[0] as f32, self.dims[1] as f32, self.dims[2] as f32) * self.block_size;
+        Aabb::new(self.origin, self.origin + size)
+    }
+}
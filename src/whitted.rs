@@ -0,0 +1,106 @@
+use nalgebra_glm::Vec3;
+
+use crate::color::Color;
+use crate::integrator::{cast_shadow, closest_intersect, offset_origin, reflect, refract, surface_color, Integrator};
+use crate::ray_differential::RayDifferential;
+use crate::sampler::Sampler;
+use crate::scene::Scene;
+
+pub const DEFAULT_MAX_DEPTH: u32 = 3;
+
+// Trazado recursivo clásico: luces directas con Phong, espejo y refracción
+pub struct Whitted {
+    // Rebotes de reflexión o refracción antes de devolver el color del cielo
+    pub max_depth: u32,
+}
+
+impl Whitted {
+    pub fn new(max_depth: u32) -> Self {
+        Whitted { max_depth }
+    }
+
+    fn cast_ray(
+        &self,
+        scene: &Scene,
+        ray_origin: &Vec3,
+        ray_direction: &Vec3,
+        differential: &RayDifferential,
+        depth: u32,
+        sampler: &mut Sampler,
+    ) -> Color {
+        if depth > self.max_depth {
+            return scene.lighting().sky_color;
+        }
+
+        let intersect = closest_intersect(ray_origin, ray_direction, &scene.objects);
+
+        if !intersect.is_intersecting {
+            return scene.lighting().sky_color;
+        }
+
+        let view_dir = (ray_origin - intersect.point).normalize();
+
+        let texture_diffuse = surface_color(&intersect, differential.footprint(ray_direction, &intersect));
+
+        let mut diffuse = Color::black();
+        let mut specular = Color::black();
+
+        for light in &scene.lighting().lights {
+            // Las luces de área reparten varias muestras estratificadas sobre su
+            // superficie; el promedio de las sombras forma la penumbra.
+            let samples = light.samples();
+            let weight = 1.0 / samples as f32;
+
+            for index in 0..samples {
+                let sample = light.illuminate(&intersect.point, sampler.stratified(index, samples));
+                let light_dir = sample.direction;
+                let reflect_dir = reflect(&-light_dir, &intersect.normal).normalize();
+
+                let shadow_intensity = cast_shadow(&intersect, &sample, &scene.objects);
+                let light_color = sample.radiance * (1.0 - shadow_intensity) * weight;
+
+                let diffuse_intensity = intersect.normal.dot(&light_dir).clamp(0.0, 1.0);
+                diffuse = diffuse + texture_diffuse * light_color * intersect.material.albedo[0] * diffuse_intensity;
+
+                let specular_intensity = view_dir.dot(&reflect_dir).max(0.0).powf(intersect.material.specular);
+                specular = specular + light_color * intersect.material.albedo[1] * specular_intensity;
+            }
+        }
+
+        let mut reflect_color = Color::black();
+        let reflectivity = intersect.material.albedo[2];
+        if reflectivity > 0.0 {
+            let reflect_dir = reflect(ray_direction, &intersect.normal).normalize();
+            let reflect_origin = offset_origin(&intersect, &reflect_dir);
+            let reflect_differential = differential.reflect(ray_direction, &intersect);
+            reflect_color = self.cast_ray(scene, &reflect_origin, &reflect_dir, &reflect_differential, depth + 1, sampler);
+        }
+
+
+        let mut refract_color = Color::black();
+        let transparency = intersect.material.albedo[3];
+        if transparency > 0.0 {
+            let refract_dir = refract(ray_direction, &intersect.normal, intersect.material.refractive_index);
+            let refract_origin = offset_origin(&intersect, &refract_dir);
+            let refract_differential = differential.transmit(ray_direction, &intersect);
+            refract_color = self.cast_ray(scene, &refract_origin, &refract_dir, &refract_differential, depth + 1, sampler);
+        }
+
+        let emissive = intersect.material.emissive_color;
+
+        (diffuse + specular) * (1.0 - reflectivity - transparency) + (reflect_color * reflectivity) + (refract_color * transparency) + emissive
+    }
+}
+
+impl Integrator for Whitted {
+    fn radiance(
+        &self,
+        scene: &Scene,
+        ray_origin: &Vec3,
+        ray_direction: &Vec3,
+        differential: &RayDifferential,
+        sampler: &mut Sampler,
+    ) -> Color {
+        self.cast_ray(scene, ray_origin, ray_direction, differential, 0, sampler)
+    }
+}